@@ -0,0 +1,422 @@
+use crate::cursor::SignatureCursor;
+use crate::decoder::{self, account, auction_instructions};
+use crate::programs;
+use crate::reconcile::highest_bid;
+use crate::rpc::Rpc;
+use crate::track::progress_line;
+use crate::BidEntry;
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Serialize;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Seconds to wait for the webhook to answer a single delivery attempt
+const WEBHOOK_TIMEOUT: u64 = 10;
+
+pub struct AlertOptions {
+    /// Our bidder (driver) wallets
+    pub bidders: Vec<Pubkey>,
+    pub webhook: String,
+    pub interval: u64,
+    /// Transactions read per bidder on the first poll; 0 only looks at bids
+    /// made after the daemon started
+    pub recent: usize,
+    /// Delivery attempts after the first one fails
+    pub retries: u32,
+    /// Alerts that could not be delivered are appended here, one JSON object
+    /// per line
+    pub dead_letter: String,
+}
+
+/// What happened to one of our bids; serialized as the webhook payload with
+/// the variant name in `event`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Alert {
+    /// A competitor's bid took the lead from ours
+    Outbid {
+        auction: String,
+        bidder: String,
+        our_amount: u64,
+        competitor: String,
+        amount: u64,
+        signature: String,
+        slot: u64,
+    },
+    /// The auction ended with one of our bidders as `AuctionState.winner`
+    Won {
+        auction: String,
+        bidder: String,
+        amount_promised: u64,
+        /// `None` when the account was already closed the first time the
+        /// auction was checked
+        amount_out_min: Option<u64>,
+    },
+    /// One of our bid transactions failed
+    BidFailed {
+        auction: String,
+        bidder: String,
+        amount: u64,
+        signature: String,
+        slot: u64,
+    },
+}
+
+impl Alert {
+    fn summary(&self) -> String {
+        match self {
+            Alert::Outbid {
+                auction,
+                our_amount,
+                competitor,
+                amount,
+                ..
+            } => format!(
+                "{} on {}: {} bid {} over our {}",
+                "Outbid".yellow(),
+                auction,
+                competitor,
+                amount,
+                our_amount
+            ),
+            Alert::Won {
+                auction,
+                bidder,
+                amount_promised,
+                ..
+            } => format!(
+                "{} {} as {} promising {}",
+                "Won".green(),
+                auction,
+                bidder,
+                amount_promised
+            ),
+            Alert::BidFailed {
+                auction,
+                amount,
+                signature,
+                ..
+            } => format!(
+                "{} on {}: bid of {} in {}",
+                "Bid Failed".red(),
+                auction,
+                amount,
+                signature
+            ),
+        }
+    }
+}
+
+/// An auction one of our bidders took part in, followed until it ends
+#[derive(Default)]
+struct WatchedAuction {
+    /// Newest signature of the auction state when its bids were last read
+    newest: Option<String>,
+    /// Bid signatures already looked at
+    seen: HashSet<String>,
+    /// Highest successful bid and its bidder; the auction program only accepts
+    /// higher bids, so this is who is leading
+    leader: Option<(u64, String)>,
+    /// Winner, amount promised and amount out min of the last AuctionState
+    /// read, used once the account is closed
+    last_state: Option<(String, u64, Option<u64>)>,
+}
+
+impl WatchedAuction {
+    /// Alerts for bids not seen before, taken in slot order
+    fn record_bids(
+        &mut self,
+        address: &str,
+        bids: &[BidEntry],
+        ours: &HashSet<String>,
+    ) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for bid in bids {
+            if !self.seen.insert(bid.signature.clone()) {
+                continue;
+            }
+            let is_ours = ours.contains(&bid.bidder);
+            if bid.failed {
+                if is_ours {
+                    alerts.push(Alert::BidFailed {
+                        auction: address.to_string(),
+                        bidder: bid.bidder.clone(),
+                        amount: bid.bid_amount,
+                        signature: bid.signature.clone(),
+                        slot: bid.slot,
+                    });
+                }
+                continue;
+            }
+            if self
+                .leader
+                .as_ref()
+                .is_some_and(|(best, _)| bid.bid_amount <= *best)
+            {
+                continue;
+            }
+            if let Some((our_amount, our_bidder)) = self
+                .leader
+                .take()
+                .filter(|(_, leader)| ours.contains(leader) && !is_ours)
+            {
+                alerts.push(Alert::Outbid {
+                    auction: address.to_string(),
+                    bidder: our_bidder,
+                    our_amount,
+                    competitor: bid.bidder.clone(),
+                    amount: bid.bid_amount,
+                    signature: bid.signature.clone(),
+                    slot: bid.slot,
+                });
+            }
+            self.leader = Some((bid.bid_amount, bid.bidder.clone()));
+        }
+        alerts
+    }
+}
+
+/// Transactions of each bidder already read, and the auctions being followed
+#[derive(Default)]
+struct Watcher {
+    cursor: SignatureCursor,
+    auctions: HashMap<String, WatchedAuction>,
+}
+
+impl Watcher {
+    /// Auction states bid on in transactions of `bidder` newer than the last
+    /// poll; the first poll reads the `recent` newest
+    fn discover_auctions(&mut self, rpc: &Rpc, bidder: &Pubkey, recent: usize) -> Result<()> {
+        let transactions = self.cursor.read_new(rpc, bidder, recent, |signature| {
+            decoder::decode_transaction(&decoder::fetch_transaction(rpc, signature)?)
+        })?;
+
+        let auction_program = programs::auction_program_id().to_string();
+        for transaction in &transactions {
+            let mut instructions = Vec::new();
+            auction_instructions(
+                &transaction.instructions,
+                &auction_program,
+                &mut instructions,
+            );
+            for instruction in instructions {
+                if instruction.name.as_deref() != Some("bid") {
+                    continue;
+                }
+                if let Some(address) = account(instruction, "auction_state") {
+                    if !self.auctions.contains_key(address) {
+                        println!(
+                            "{}",
+                            progress_line(&format!("{}: {}", "Watching".green(), address))
+                        );
+                        self.auctions
+                            .insert(address.to_string(), WatchedAuction::default());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// New bids on a followed auction, and whether it has ended. An auction
+    /// has ended once its result is posted to Wormhole (`seq_msg` is set) or
+    /// its account is closed.
+    async fn check_auction(
+        &mut self,
+        rpc: &Rpc,
+        address: &str,
+        ours: &HashSet<String>,
+    ) -> Result<(Vec<Alert>, bool)> {
+        let pubkey =
+            Pubkey::from_str(address).context("Failed to parse auction state address as Pubkey")?;
+        let auction = self.auctions.entry(address.to_string()).or_default();
+
+        let mut alerts = Vec::new();
+        let newest = rpc
+            .get_signatures_for_address(&pubkey)?
+            .first()
+            .map(|sig_info| sig_info.signature.clone());
+        let mut bids = None;
+        if newest != auction.newest {
+            let (history, _) = crate::get_bid_history(address, rpc).await?;
+            alerts.extend(auction.record_bids(address, &history, ours));
+            auction.newest = newest;
+            bids = Some(history);
+        }
+
+        // Only a confirmed missing account means it was closed; a failed read
+        // is retried on the next poll
+        let ended = match rpc.get_optional_account(&pubkey)? {
+            (Some(account), _) => {
+                let state = crate::parse_auction_state_data(&account.data)?;
+                auction.last_state = Some((
+                    state.winner.to_string(),
+                    state.amount_promised,
+                    Some(state.amount_out_min),
+                ));
+                state.seq_msg != 0
+            }
+            (None, _) => {
+                // Closed before it was first read: the highest successful bid
+                // of the history won, as in my-bids
+                if auction.last_state.is_none() {
+                    let bids = match bids {
+                        Some(bids) => bids,
+                        None => crate::get_bid_history(address, rpc).await?.0,
+                    };
+                    auction.last_state = highest_bid(&bids, None)
+                        .map(|bid| (bid.bidder.clone(), bid.bid_amount, None));
+                }
+                true
+            }
+        };
+        if ended {
+            if let Some((winner, amount_promised, amount_out_min)) = &auction.last_state {
+                if ours.contains(winner) {
+                    alerts.push(Alert::Won {
+                        auction: address.to_string(),
+                        bidder: winner.clone(),
+                        amount_promised: *amount_promised,
+                        amount_out_min: *amount_out_min,
+                    });
+                }
+            }
+        }
+        Ok((alerts, ended))
+    }
+}
+
+/// Posts alerts to the webhook, retrying with exponential backoff and writing
+/// those that still fail to the dead-letter file
+struct Webhook {
+    client: reqwest::Client,
+    url: String,
+    retries: u32,
+    dead_letter: String,
+}
+
+impl Webhook {
+    async fn post(&self, payload: &serde_json::Value) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(payload)
+            .send()
+            .await
+            .context("Failed to send webhook request")?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Webhook responded with status: {}",
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+
+    async fn deliver(&self, alert: &Alert) {
+        println!("{}", progress_line(&alert.summary()));
+        let mut payload = serde_json::to_value(alert).unwrap_or_default();
+        payload["timestamp"] = json!(chrono::Utc::now().timestamp());
+
+        let mut last_error = None;
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_secs(1 << (attempt - 1).min(6))).await;
+            }
+            match self.post(&payload).await {
+                Ok(()) => return,
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let error = last_error.map(|e| format!("{:#}", e)).unwrap_or_default();
+        println!(
+            "{}",
+            progress_line(&format!(
+                "{}: delivery failed after {} attempts: {}",
+                "Warning".yellow(),
+                self.retries + 1,
+                error
+            ))
+        );
+        let entry = json!({
+            "payload": payload,
+            "error": error,
+            "attempts": self.retries + 1,
+            "failed_at": chrono::Utc::now().timestamp(),
+        });
+        if let Err(e) = self.write_dead_letter(&entry) {
+            eprintln!("Error: {:#}", e);
+        }
+    }
+
+    fn write_dead_letter(&self, entry: &serde_json::Value) -> Result<()> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.dead_letter)
+            .with_context(|| format!("Failed to open dead-letter file {}", self.dead_letter))?;
+        writeln!(file, "{}", entry)
+            .with_context(|| format!("Failed to write dead-letter file {}", self.dead_letter))
+    }
+}
+
+/// Follow the auctions our bidders take part in every `interval` seconds and
+/// post an alert when we are outbid, win or a bid fails, until the process is
+/// stopped
+pub async fn run(rpc: Rpc, options: AlertOptions) -> Result<()> {
+    let webhook = Webhook {
+        client: reqwest::Client::builder()
+            .timeout(Duration::from_secs(WEBHOOK_TIMEOUT))
+            .build()
+            .context("Failed to build webhook client")?,
+        url: options.webhook.clone(),
+        retries: options.retries,
+        dead_letter: options.dead_letter.clone(),
+    };
+    let ours: HashSet<String> = options.bidders.iter().map(|b| b.to_string()).collect();
+    let mut watcher = Watcher::default();
+    println!(
+        "{}",
+        progress_line(&format!(
+            "{}: {} bidders, posting to {}",
+            "Alerting".green(),
+            options.bidders.len(),
+            options.webhook
+        ))
+    );
+
+    loop {
+        for bidder in &options.bidders {
+            if let Err(e) = watcher.discover_auctions(&rpc, bidder, options.recent) {
+                eprintln!("Warning: failed to poll {}: {:#}", bidder, e);
+            }
+        }
+
+        let addresses: Vec<String> = watcher.auctions.keys().cloned().collect();
+        for address in addresses {
+            match watcher.check_auction(&rpc, &address, &ours).await {
+                Ok((alerts, ended)) => {
+                    for alert in &alerts {
+                        webhook.deliver(alert).await;
+                    }
+                    if ended {
+                        watcher.auctions.remove(&address);
+                        println!(
+                            "{}",
+                            progress_line(&format!("{}: {}", "Ended".green(), address))
+                        );
+                    }
+                }
+                Err(e) => eprintln!("Warning: failed to check auction {}: {:#}", address, e),
+            }
+        }
+
+        tokio::time::sleep(Duration::from_secs(options.interval)).await;
+    }
+}
@@ -0,0 +1,189 @@
+use crate::errors;
+use crate::order::OrderInfo;
+use crate::programs;
+use crate::rpc::Rpc;
+use anyhow::{Context, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+use colored::*;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_sdk::clock::Slot;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::{
+    EncodedTransaction, UiInstruction, UiMessage, UiParsedInstruction, UiTransactionEncoding,
+};
+use std::str::FromStr;
+
+/// Anchor instruction discriminator: first 8 bytes of sha256("global:<name>")
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    let hash = solana_sdk::hash::hash(format!("global:{}", name).as_bytes());
+    hash.to_bytes()[..8].try_into().unwrap()
+}
+
+/// Arguments of the auction program's `bid` instruction
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct BidArgs {
+    pub order: OrderInfo,
+    pub amount_bid: u64,
+}
+
+pub fn build_bid_instruction(
+    auction_state: &Pubkey,
+    bidder: &Pubkey,
+    order: &OrderInfo,
+    amount: u64,
+) -> Result<Instruction> {
+    let config = Pubkey::find_program_address(&[b"config"], &programs::auction_program_id()).0;
+
+    let mut data = instruction_discriminator("bid").to_vec();
+    BidArgs {
+        order: order.clone(),
+        amount_bid: amount,
+    }
+    .serialize(&mut data)
+    .context("Failed to serialize bid arguments")?;
+
+    Ok(Instruction {
+        program_id: programs::auction_program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new(*auction_state, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    })
+}
+
+/// ComputeBudget instructions to prepend to a bid; `unit_price` is in micro-lamports per compute unit
+pub fn compute_budget_instructions(
+    unit_limit: Option<u32>,
+    unit_price: Option<u64>,
+) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    if let Some(limit) = unit_limit {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+    }
+    if let Some(price) = unit_price {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    instructions
+}
+
+/// Decode the arguments of a `bid` instruction, if `data` is one
+pub fn decode_bid_args(data: &[u8]) -> Option<BidArgs> {
+    let discriminator = instruction_discriminator("bid");
+    if data.len() < 8 || data[..8] != discriminator {
+        return None;
+    }
+    BidArgs::try_from_slice(&data[8..]).ok()
+}
+
+/// Recover the order parameters of an auction from any earlier bid on it,
+/// since every bid instruction carries the full `OrderInfo`
+pub fn find_order_info_in_history(rpc: &Rpc, auction_state: &Pubkey) -> Result<Option<OrderInfo>> {
+    let signatures = rpc
+        .get_signatures_for_address(auction_state)
+        .context("Failed to get signatures for auction state address")?;
+
+    for sig_info in signatures.iter().take(100) {
+        let signature = Signature::from_str(&sig_info.signature)?;
+        let transaction = rpc.get_transaction(&signature, UiTransactionEncoding::JsonParsed)?;
+
+        let message = match &transaction.transaction.transaction {
+            EncodedTransaction::Json(parsed_tx) => match &parsed_tx.message {
+                UiMessage::Parsed(parsed_msg) => parsed_msg.clone(),
+                _ => continue,
+            },
+            _ => continue,
+        };
+
+        let auction_program = programs::auction_program_id().to_string();
+        for instruction in &message.instructions {
+            if let UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(parsed)) =
+                instruction
+            {
+                if parsed.program_id != auction_program {
+                    continue;
+                }
+                let data = match bs58::decode(&parsed.data).into_vec() {
+                    Ok(data) => data,
+                    Err(_) => continue,
+                };
+                if let Some(args) = decode_bid_args(&data) {
+                    return Ok(Some(args.order));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Simulate `instructions` as a transaction paid by `payer`, without signatures
+/// and with the latest blockhash substituted by the node; returns the result
+/// with the context slot the simulation ran at
+pub fn simulate_instructions(
+    rpc: &Rpc,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+) -> Result<(RpcSimulateTransactionResult, Slot)> {
+    let message = Message::new(instructions, Some(payer));
+    let transaction = Transaction::new_unsigned(message);
+
+    rpc.simulate_transaction(
+        &transaction,
+        RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            encoding: Some(UiTransactionEncoding::Base64),
+            ..RpcSimulateTransactionConfig::default()
+        },
+    )
+}
+
+pub fn format_simulation(result: &RpcSimulateTransactionResult, context_slot: Slot) -> String {
+    let logs = result.logs.clone().unwrap_or_default();
+
+    let mut output = format!(
+        "Simulation Result:
+  {}: {}
+  {}: {}
+  {}: {}",
+        "Context Slot".green(),
+        context_slot,
+        "Status".green(),
+        if result.err.is_some() {
+            "Failed".red()
+        } else {
+            "Success".green()
+        },
+        "Compute Units".green(),
+        result
+            .units_consumed
+            .map(|units| units.to_string())
+            .unwrap_or_else(|| "Unknown".to_string())
+    );
+
+    if let Some(err) = &result.err {
+        let decoded = errors::decode_transaction_error(err, &logs);
+        output.push_str(&format!(
+            "\n  {}: {}",
+            "Error".red(),
+            errors::format_decoded_error(&decoded)
+        ));
+    }
+
+    output.push_str(&format!("\n  {}:", "Logs".green()));
+    for log in &logs {
+        output.push_str(&format!("\n    {}", log));
+    }
+
+    output
+}
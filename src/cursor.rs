@@ -0,0 +1,86 @@
+use crate::rpc::Rpc;
+use anyhow::Result;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+
+/// Signatures remembered to skip transactions already read through another
+/// polled account
+const PROCESSED_SIGNATURES: usize = 10_000;
+/// Signatures requested per page; the most an RPC node returns at once
+const PAGE_SIZE: usize = 1000;
+
+/// Newest signature read from each polled account, and the transactions
+/// already read, so that each poll only looks at what is new
+#[derive(Default)]
+pub struct SignatureCursor {
+    newest: HashMap<Pubkey, String>,
+    processed: HashSet<String>,
+    processed_order: VecDeque<String>,
+}
+
+impl SignatureCursor {
+    /// Runs `read` on the transactions of `address` newer than the last poll,
+    /// oldest first, skipping those already read; the first poll reads the
+    /// `recent` newest. Signatures are paged back until the newest one of the
+    /// last poll, so a burst of more than a page between polls is not missed.
+    /// The transactions are only marked read and the cursor only advanced once
+    /// `read` succeeded for all of them, so a failed read is retried on the
+    /// next poll.
+    pub fn read_new<T>(
+        &mut self,
+        rpc: &Rpc,
+        address: &Pubkey,
+        recent: usize,
+        mut read: impl FnMut(&Signature) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let newest = self.newest.get(address);
+        let mut signatures: Vec<RpcConfirmedTransactionStatusWithSignature> = Vec::new();
+        loop {
+            let before = signatures.last().map(|sig_info| sig_info.signature.clone());
+            let page = rpc.get_signatures_for_address_page(
+                address,
+                before.as_deref(),
+                newest.map(String::as_str),
+                PAGE_SIZE,
+            )?;
+            let full = page.len() == PAGE_SIZE;
+            signatures.extend(page);
+            if !full || (newest.is_none() && signatures.len() >= recent) {
+                break;
+            }
+        }
+        let fresh: Vec<_> = signatures
+            .iter()
+            .take_while(|sig_info| Some(&sig_info.signature) != newest)
+            .take(if newest.is_some() { usize::MAX } else { recent })
+            .filter(|sig_info| !self.processed.contains(&sig_info.signature))
+            .collect();
+
+        let mut results = Vec::new();
+        for sig_info in fresh.iter().rev() {
+            results.push(read(&Signature::from_str(&sig_info.signature)?)?);
+        }
+        for sig_info in fresh {
+            self.mark_processed(&sig_info.signature);
+        }
+        if let Some(first) = signatures.first() {
+            self.newest.insert(*address, first.signature.clone());
+        }
+        Ok(results)
+    }
+
+    fn mark_processed(&mut self, signature: &str) {
+        if !self.processed.insert(signature.to_string()) {
+            return;
+        }
+        self.processed_order.push_back(signature.to_string());
+        if self.processed_order.len() > PROCESSED_SIGNATURES {
+            if let Some(oldest) = self.processed_order.pop_front() {
+                self.processed.remove(&oldest);
+            }
+        }
+    }
+}
@@ -0,0 +1,592 @@
+use crate::bid::instruction_discriminator;
+use crate::errors;
+use crate::events::{self, DecodedEvent};
+use crate::idl;
+use crate::order::{self, OrderInfo};
+use crate::programs;
+use crate::rpc::Rpc;
+use anyhow::{Context, Result};
+use borsh::BorshDeserialize;
+use colored::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::TransactionError;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiInstruction,
+    UiLoadedAddresses, UiMessage, UiTransactionEncoding,
+};
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+/// Borsh argument types used by the Mayan programs
+#[derive(Debug, Clone, Copy)]
+pub enum ArgType {
+    U8,
+    U16,
+    U64,
+    Bool,
+    Bytes32,
+    Pubkey,
+    Order,
+}
+
+pub struct InstructionDef {
+    pub name: &'static str,
+    pub accounts: &'static [&'static str],
+    pub args: &'static [(&'static str, ArgType)],
+}
+
+pub struct ProgramDef {
+    pub name: &'static str,
+    /// A function so that the configured program IDs are picked up
+    pub id: fn() -> Pubkey,
+    pub instructions: &'static [InstructionDef],
+}
+
+pub const KNOWN_PROGRAMS: &[ProgramDef] = &[
+    ProgramDef {
+        name: "Swift Auction",
+        id: programs::auction_program_id,
+        instructions: &[
+            InstructionDef {
+                name: "bid",
+                accounts: &["config", "driver", "auction_state", "system_program"],
+                args: &[("order", ArgType::Order), ("amount_bid", ArgType::U64)],
+            },
+            InstructionDef {
+                name: "post_auction",
+                accounts: &[
+                    "config",
+                    "driver",
+                    "auction_state",
+                    "emitter",
+                    "wormhole_config",
+                    "wormhole_message",
+                    "sequence",
+                    "fee_collector",
+                    "clock",
+                    "rent",
+                    "system_program",
+                    "wormhole_program",
+                ],
+                args: &[("order", ArgType::Order), ("driver_dest", ArgType::Bytes32)],
+            },
+            InstructionDef {
+                name: "close_auction",
+                accounts: &["auction_state", "initializer"],
+                args: &[],
+            },
+        ],
+    },
+    ProgramDef {
+        name: "Swift",
+        id: programs::swift_program_id,
+        instructions: &[
+            // After the input amount and fees, the order fields follow
+            // `order::OrderInfo`, the order the hash is computed over; the
+            // trader, source chain and input token come from the accounts
+            InstructionDef {
+                name: "init_order",
+                accounts: &[
+                    "trader",
+                    "relayer",
+                    "state",
+                    "state_from_acc",
+                    "relayer_fee_acc",
+                    "mint_from",
+                    "fee_manager_program",
+                    "token_program",
+                    "system_program",
+                ],
+                args: &[
+                    ("amount_in_min", ArgType::U64),
+                    ("native_input", ArgType::Bool),
+                    ("fee_submit", ArgType::U64),
+                    ("addr_dest", ArgType::Bytes32),
+                    ("chain_dest", ArgType::U16),
+                    ("token_out", ArgType::Bytes32),
+                    ("amount_out_min", ArgType::U64),
+                    ("gas_drop", ArgType::U64),
+                    ("fee_cancel", ArgType::U64),
+                    ("fee_refund", ArgType::U64),
+                    ("deadline", ArgType::U64),
+                    ("penalty_period", ArgType::U16),
+                    ("referrer_addr", ArgType::Bytes32),
+                    ("referrer_bps", ArgType::U8),
+                    ("protocol_bps", ArgType::U8),
+                    ("auction_mode", ArgType::U8),
+                    ("key_rnd", ArgType::Bytes32),
+                ],
+            },
+            InstructionDef {
+                name: "set_auction_winner",
+                accounts: &["state", "auction"],
+                args: &[("expected_winner", ArgType::Pubkey)],
+            },
+            InstructionDef {
+                name: "fulfill",
+                accounts: &[
+                    "state",
+                    "driver",
+                    "state_to_acc",
+                    "mint_to",
+                    "dest",
+                    "system_program",
+                ],
+                args: &[("addr_unlocker", ArgType::Bytes32)],
+            },
+            InstructionDef {
+                name: "settle",
+                accounts: &[
+                    "state",
+                    "state_to_acc",
+                    "relayer",
+                    "mint_to",
+                    "dest",
+                    "referrer",
+                    "fee_collector",
+                    "referrer_fee_acc",
+                    "mayan_fee_acc",
+                    "dest_acc",
+                    "token_program",
+                    "system_program",
+                    "associated_token_program",
+                ],
+                args: &[("try_close_ata", ArgType::Bool)],
+            },
+            InstructionDef {
+                name: "unlock",
+                accounts: &[
+                    "vaa_unlock",
+                    "state",
+                    "state_from_acc",
+                    "unlocker",
+                    "driver_acc",
+                    "mint_from",
+                    "token_program",
+                    "system_program",
+                ],
+                args: &[],
+            },
+            InstructionDef {
+                name: "unlock_batch",
+                accounts: &["vaa_unlock", "unlocker"],
+                args: &[],
+            },
+            InstructionDef {
+                name: "refund",
+                accounts: &[
+                    "vaa_cancel",
+                    "state",
+                    "state_from_acc",
+                    "trader",
+                    "trader_acc",
+                    "relayer_cancel",
+                    "relayer_cancel_acc",
+                    "relayer_refund",
+                    "relayer_refund_acc",
+                    "mint_from",
+                    "token_program",
+                    "system_program",
+                ],
+                args: &[],
+            },
+            InstructionDef {
+                name: "cancel",
+                accounts: &["state", "relayer"],
+                args: &[],
+            },
+            InstructionDef {
+                name: "close",
+                accounts: &["state", "relayer"],
+                args: &[],
+            },
+        ],
+    },
+];
+
+#[derive(Debug, Clone)]
+pub struct DecodedInstruction {
+    pub program_id: String,
+    pub program_name: Option<&'static str>,
+    pub name: Option<String>,
+    /// (account name, pubkey); unnamed accounts are labelled by position
+    pub accounts: Vec<(String, String)>,
+    pub args: Vec<(String, String)>,
+    /// Instruction data that was not decoded into args
+    pub remaining_data: Vec<u8>,
+    pub inner: Vec<DecodedInstruction>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub signer: String,
+    pub fee: u64,
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+    pub instructions: Vec<DecodedInstruction>,
+    pub events: Vec<DecodedEvent>,
+}
+
+/// Pubkey of the account `name` of a decoded instruction
+pub fn account<'a>(instruction: &'a DecodedInstruction, name: &str) -> Option<&'a str> {
+    instruction
+        .accounts
+        .iter()
+        .find(|(account, _)| account == name)
+        .map(|(_, pubkey)| pubkey.as_str())
+}
+
+/// Value of the decoded argument `name`
+pub fn arg<'a>(instruction: &'a DecodedInstruction, name: &str) -> Option<&'a str> {
+    instruction
+        .args
+        .iter()
+        .find(|(arg, _)| arg == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Auction program instructions of a transaction, including those invoked
+/// through CPI
+pub fn auction_instructions<'a>(
+    instructions: &'a [DecodedInstruction],
+    auction_program: &str,
+    found: &mut Vec<&'a DecodedInstruction>,
+) {
+    for instruction in instructions {
+        if instruction.program_id == auction_program {
+            found.push(instruction);
+        }
+        auction_instructions(&instruction.inner, auction_program, found);
+    }
+}
+
+fn decode_arg(data: &mut &[u8], ty: ArgType) -> Result<Vec<(String, String)>> {
+    let value = match ty {
+        ArgType::U8 => u8::deserialize(data)?.to_string(),
+        ArgType::U16 => u16::deserialize(data)?.to_string(),
+        ArgType::U64 => u64::deserialize(data)?.to_string(),
+        ArgType::Bool => bool::deserialize(data)?.to_string(),
+        ArgType::Bytes32 => hex::encode(<[u8; 32]>::deserialize(data)?),
+        ArgType::Pubkey => Pubkey::new_from_array(<[u8; 32]>::deserialize(data)?).to_string(),
+        ArgType::Order => {
+            let order = OrderInfo::deserialize(data)?;
+            return Ok(order::order_fields(&order));
+        }
+    };
+    Ok(vec![(String::new(), value)])
+}
+
+/// Decode Borsh fields in order, stopping at the first one that does not fit;
+/// `data` is advanced past the decoded fields
+pub fn decode_args(data: &mut &[u8], args: &[(&str, ArgType)]) -> Vec<(String, String)> {
+    let mut decoded = Vec::new();
+    for (arg_name, ty) in args {
+        // Decode from a copy so a failed field leaves `data` at its start
+        let mut attempt = *data;
+        match decode_arg(&mut attempt, *ty) {
+            Ok(fields) => {
+                *data = attempt;
+                decoded.extend(fields.into_iter().map(|(field, value)| {
+                    if field.is_empty() {
+                        (arg_name.to_string(), value)
+                    } else {
+                        (format!("{}.{}", arg_name, field), value)
+                    }
+                }));
+            }
+            // Layout drifted from what we know, keep the undecoded bytes
+            Err(_) => break,
+        }
+    }
+    decoded
+}
+
+/// Decode one instruction of a known Mayan program by its Anchor discriminator;
+/// anything else is returned with only the program ID, positional accounts and raw data
+pub fn decode_instruction(
+    program_id: &str,
+    accounts: &[String],
+    data: &[u8],
+) -> DecodedInstruction {
+    let positional = |names: &[&str]| -> Vec<(String, String)> {
+        accounts
+            .iter()
+            .enumerate()
+            .map(|(i, account)| {
+                let name = names
+                    .get(i)
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| format!("#{}", i));
+                (name, account.clone())
+            })
+            .collect()
+    };
+
+    let mut decoded = DecodedInstruction {
+        program_id: program_id.to_string(),
+        program_name: None,
+        name: None,
+        accounts: positional(&[]),
+        args: Vec::new(),
+        remaining_data: data.to_vec(),
+        inner: Vec::new(),
+    };
+
+    // A loaded IDL takes precedence over the built-in tables
+    if let Some(idl) = Pubkey::from_str(program_id)
+        .ok()
+        .and_then(|id| idl::get(&id))
+    {
+        decoded.program_name = Some(idl.name);
+        let Some(instruction) = idl.instruction(data) else {
+            return decoded;
+        };
+        let names: Vec<&str> = instruction.accounts.iter().map(String::as_str).collect();
+        decoded.name = Some(instruction.name.clone());
+        decoded.accounts = positional(&names);
+
+        let mut rest = &data[instruction.discriminator.len()..];
+        decoded.args = idl.decode_args(instruction, &mut rest);
+        decoded.remaining_data = rest.to_vec();
+        return decoded;
+    }
+
+    let Some(program) = KNOWN_PROGRAMS
+        .iter()
+        .find(|program| (program.id)().to_string() == program_id)
+    else {
+        return decoded;
+    };
+    decoded.program_name = Some(program.name);
+
+    if data.len() < 8 {
+        return decoded;
+    }
+    let Some(instruction) = program
+        .instructions
+        .iter()
+        .find(|instruction| instruction_discriminator(instruction.name) == data[..8])
+    else {
+        return decoded;
+    };
+
+    decoded.name = Some(instruction.name.to_string());
+    decoded.accounts = positional(instruction.accounts);
+
+    let mut rest = &data[8..];
+    decoded.args = decode_args(&mut rest, instruction.args);
+    decoded.remaining_data = rest.to_vec();
+
+    decoded
+}
+
+/// Programs invoked by a transaction, including through CPI
+pub fn program_ids(instructions: &[DecodedInstruction], ids: &mut BTreeSet<String>) {
+    for instruction in instructions {
+        ids.insert(instruction.program_id.clone());
+        program_ids(&instruction.inner, ids);
+    }
+}
+
+pub fn fetch_transaction(
+    rpc: &Rpc,
+    signature: &Signature,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    rpc.get_transaction(signature, UiTransactionEncoding::Json)
+}
+
+/// Decode every instruction of a transaction fetched with `Json` encoding,
+/// attaching inner (CPI) instructions to the top-level instruction that made them
+pub fn decode_transaction(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<DecodedTransaction> {
+    let ui_transaction = match &transaction.transaction.transaction {
+        EncodedTransaction::Json(ui_transaction) => ui_transaction,
+        _ => return Err(anyhow::anyhow!("Unsupported transaction encoding")),
+    };
+    let message = match &ui_transaction.message {
+        UiMessage::Raw(message) => message,
+        _ => return Err(anyhow::anyhow!("Unsupported transaction message format")),
+    };
+    let meta = transaction
+        .transaction
+        .meta
+        .as_ref()
+        .ok_or(anyhow::anyhow!("Failed to get transaction meta"))?;
+
+    // Static keys first, then keys loaded from address lookup tables
+    let mut account_keys = message.account_keys.clone();
+    if let Some(loaded) = Option::<UiLoadedAddresses>::from(meta.loaded_addresses.clone()) {
+        account_keys.extend(loaded.writable);
+        account_keys.extend(loaded.readonly);
+    }
+
+    let resolve =
+        |program_id_index: u8, accounts: &[u8], data: &str| -> Result<DecodedInstruction> {
+            let program_id = account_keys
+                .get(program_id_index as usize)
+                .cloned()
+                .unwrap_or_default();
+            let accounts: Vec<String> = accounts
+                .iter()
+                .map(|index| {
+                    account_keys
+                        .get(*index as usize)
+                        .cloned()
+                        .unwrap_or_default()
+                })
+                .collect();
+            let data = bs58::decode(data)
+                .into_vec()
+                .context("Failed to decode instruction data")?;
+            Ok(decode_instruction(&program_id, &accounts, &data))
+        };
+
+    let mut instructions = message
+        .instructions
+        .iter()
+        .map(|instruction| {
+            resolve(
+                instruction.program_id_index,
+                &instruction.accounts,
+                &instruction.data,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let inner_instructions: Option<Vec<_>> = meta.inner_instructions.clone().into();
+    for inner in inner_instructions.unwrap_or_default() {
+        let Some(parent) = instructions.get_mut(inner.index as usize) else {
+            continue;
+        };
+        for instruction in &inner.instructions {
+            if let UiInstruction::Compiled(compiled) = instruction {
+                parent.inner.push(resolve(
+                    compiled.program_id_index,
+                    &compiled.accounts,
+                    &compiled.data,
+                )?);
+            }
+        }
+    }
+
+    let logs: Vec<String> = Option::from(meta.log_messages.clone()).unwrap_or_default();
+
+    Ok(DecodedTransaction {
+        signature: ui_transaction
+            .signatures
+            .first()
+            .cloned()
+            .unwrap_or_default(),
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        signer: account_keys.first().cloned().unwrap_or_default(),
+        fee: meta.fee,
+        err: meta.err.clone(),
+        events: events::decode_events_from_logs(&logs),
+        logs,
+        instructions,
+    })
+}
+
+fn format_instruction(
+    result: &mut String,
+    label: &str,
+    instruction: &DecodedInstruction,
+    indent: &str,
+) {
+    let title = match (instruction.program_name, &instruction.name) {
+        (Some(program), Some(name)) => format!("{}: {}", program, name),
+        (Some(program), None) => format!("{}: unknown instruction", program),
+        (None, _) => "Unknown program".to_string(),
+    };
+    result.push_str(&format!(
+        "\n{}{} {}: {}\n{}  {}: {}",
+        indent,
+        "Instruction".cyan(),
+        label,
+        title,
+        indent,
+        "Program".green(),
+        instruction.program_id
+    ));
+
+    if !instruction.accounts.is_empty() {
+        result.push_str(&format!("\n{}  {}:", indent, "Accounts".green()));
+        for (name, account) in &instruction.accounts {
+            result.push_str(&format!("\n{}    {}: {}", indent, name, account));
+        }
+    }
+    if !instruction.args.is_empty() {
+        result.push_str(&format!("\n{}  {}:", indent, "Args".green()));
+        for (name, value) in &instruction.args {
+            result.push_str(&format!("\n{}    {}: {}", indent, name, value));
+        }
+    }
+    if !instruction.remaining_data.is_empty() {
+        result.push_str(&format!(
+            "\n{}  {}: {}",
+            indent,
+            "Data".green(),
+            hex::encode(&instruction.remaining_data)
+        ));
+    }
+
+    for (i, inner) in instruction.inner.iter().enumerate() {
+        format_instruction(
+            result,
+            &format!("{}.{}", label, i + 1),
+            inner,
+            &format!("{}  ", indent),
+        );
+    }
+}
+
+pub fn format_decoded_transaction(transaction: &DecodedTransaction) -> String {
+    let status = match &transaction.err {
+        None => "Success".green().to_string(),
+        Some(err) => format!(
+            "{} ({})",
+            "Failed".red(),
+            errors::format_decoded_error(&errors::decode_transaction_error(err, &transaction.logs))
+        ),
+    };
+
+    let mut result = format!(
+        "Transaction Details:
+  {}: {}
+  {}: {}
+  {}: {}
+  {}: {}
+  {}: {}
+  {}: {}",
+        "Signature".green(),
+        transaction.signature,
+        "Slot".green(),
+        transaction.slot,
+        "Block Time".green(),
+        transaction.block_time.unwrap_or(0),
+        "Signer".green(),
+        transaction.signer,
+        "Fee".green(),
+        transaction.fee,
+        "Status".green(),
+        status
+    );
+
+    for (i, instruction) in transaction.instructions.iter().enumerate() {
+        result.push('\n');
+        format_instruction(&mut result, &(i + 1).to_string(), instruction, "");
+    }
+
+    if !transaction.events.is_empty() {
+        result.push_str(&format!("\n\n{}:", "Events".green()));
+        result.push_str(&events::format_events(&transaction.events, "  "));
+    }
+
+    result
+}
@@ -0,0 +1,267 @@
+use crate::latency::{histogram, percentile};
+use crate::order::{format_ui_amount, parse_ui_amount, record_str, record_u64};
+use crate::transport::HttpApi;
+use crate::AuctionState;
+use anyhow::{Context, Result};
+use colored::*;
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+/// What an order swapped, from its explorer record
+#[derive(Debug, Clone)]
+pub struct OrderInput {
+    pub source_chain: Option<u64>,
+    pub token_in: Option<String>,
+    /// Input amount in UI units, as reported by the explorer
+    pub amount_in: Option<String>,
+    pub dest_chain: Option<u64>,
+    pub token_out: Option<String>,
+    /// Decimals of the output amounts on-chain: the output token's decimals,
+    /// normalized to at most 8 as the Swift program does
+    pub decimals: u32,
+}
+
+impl OrderInput {
+    pub fn from_explorer_record(record: &Value) -> Self {
+        OrderInput {
+            source_chain: record_u64(record, &["sourceChain"]),
+            token_in: record_str(record, &["swiftInputContract", "fromTokenAddress"]),
+            amount_in: record_str(record, &["fromAmount"]),
+            dest_chain: record_u64(record, &["destChain"]),
+            token_out: record_str(record, &["toTokenAddress"]),
+            decimals: record_u64(record, &["toTokenDecimals", "toToken.decimals"])
+                .unwrap_or(8)
+                .min(8) as u32,
+        }
+    }
+}
+
+/// Client of a quote API, asked what an order's input should get on its route
+pub struct QuoteApi {
+    http: Arc<dyn HttpApi>,
+    base_url: String,
+}
+
+impl QuoteApi {
+    pub fn new(http: Arc<dyn HttpApi>, base_url: String) -> Self {
+        QuoteApi { http, base_url }
+    }
+
+    /// Expected output of the order's input, in the order's on-chain units.
+    /// The API is called as `GET <base>/quote?amountIn=&fromToken=&fromChain=&toToken=&toChain=`
+    /// with Wormhole chain IDs, and answers with a quote (or a list whose first
+    /// entry is the best one) carrying `expectedAmountOut` in UI units.
+    pub async fn quote(&self, input: &OrderInput) -> Result<u64> {
+        fn require<T: ToString>(value: &Option<T>, name: &str) -> Result<String> {
+            value
+                .as_ref()
+                .map(|value| value.to_string())
+                .ok_or_else(|| anyhow::anyhow!("Explorer record has no {}", name))
+        }
+
+        let url = format!(
+            "{}/quote?amountIn={}&fromToken={}&fromChain={}&toToken={}&toChain={}",
+            self.base_url.trim_end_matches('/'),
+            require(&input.amount_in, "fromAmount")?,
+            require(&input.token_in, "fromTokenAddress")?,
+            require(&input.source_chain, "sourceChain")?,
+            require(&input.token_out, "toTokenAddress")?,
+            require(&input.dest_chain, "destChain")?
+        );
+        let response = self
+            .http
+            .get(&url)
+            .await
+            .context("Failed to send request to quote API")?;
+        if !response.is_success() {
+            return Err(anyhow::anyhow!(
+                "Quote API request failed with status: {}",
+                response.status
+            ));
+        }
+
+        let body: Value = response.json().context("Failed to parse quote response")?;
+        let quote = match &body {
+            Value::Array(quotes) => quotes
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("Quote API returned no quotes"))?,
+            quote => quote,
+        };
+        let amount = record_str(quote, &["expectedAmountOut", "amountOut"])
+            .ok_or_else(|| anyhow::anyhow!("Quote has no expectedAmountOut: {}", quote))?;
+        parse_ui_amount(&amount, input.decimals)
+    }
+}
+
+/// An order's auction result next to its minimum and the reference quote
+pub struct OrderEfficiency {
+    pub order_id: String,
+    pub auction: String,
+    pub input: OrderInput,
+    pub amount_out_min: u64,
+    /// The quote, or why there is none
+    pub quote: Result<u64, String>,
+    /// `None` while no bid has won
+    pub amount_promised: Option<u64>,
+}
+
+/// Difference of `amount` from `reference` in basis points of `reference`
+fn bps(amount: u64, reference: u64) -> Option<i64> {
+    if reference == 0 {
+        return None;
+    }
+    Some(((amount as f64 - reference as f64) * 10_000.0 / reference as f64).round() as i64)
+}
+
+impl OrderEfficiency {
+    pub fn new(
+        order_id: &str,
+        auction: &str,
+        input: OrderInput,
+        state: &AuctionState,
+        quote: Result<u64>,
+    ) -> Self {
+        let won = state.winner != Pubkey::default() && state.amount_promised > 0;
+        OrderEfficiency {
+            order_id: order_id.to_string(),
+            auction: auction.to_string(),
+            input,
+            amount_out_min: state.amount_out_min,
+            quote: quote.map_err(|e| format!("{:#}", e)),
+            amount_promised: won.then_some(state.amount_promised),
+        }
+    }
+
+    /// Surplus of the winning bid over the order's minimum
+    pub fn over_min_bps(&self) -> Option<i64> {
+        bps(self.amount_promised?, self.amount_out_min)
+    }
+
+    /// Winning bid against the reference quote; negative when it fell short
+    pub fn vs_quote_bps(&self) -> Option<i64> {
+        bps(self.amount_promised?, *self.quote.as_ref().ok()?)
+    }
+
+    /// Room the reference quote left above the order's minimum
+    pub fn quote_over_min_bps(&self) -> Option<i64> {
+        bps(*self.quote.as_ref().ok()?, self.amount_out_min)
+    }
+}
+
+fn format_bps(bps: Option<i64>) -> String {
+    bps.map(|bps| format!("{:+} bps", bps))
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+pub fn format_order_efficiency(order: &OrderEfficiency) -> String {
+    let amount = |amount: u64| {
+        format!(
+            "{} ({})",
+            amount,
+            format_ui_amount(amount, order.input.decimals)
+        )
+    };
+    let input = &order.input;
+    let mut result = format!(
+        "{} {}\n  {}: {}\n  {}: {} {} (chain {}) -> {} (chain {})\n  {}: {}\n  {}: {}\n  {}: {}",
+        "Order".cyan(),
+        order.order_id,
+        "Auction State".green(),
+        order.auction,
+        "Input".green(),
+        input.amount_in.as_deref().unwrap_or("Unknown"),
+        input.token_in.as_deref().unwrap_or("Unknown"),
+        optional(input.source_chain),
+        input.token_out.as_deref().unwrap_or("Unknown"),
+        optional(input.dest_chain),
+        "Amount Out Min".green(),
+        amount(order.amount_out_min),
+        "Reference Quote".green(),
+        match &order.quote {
+            Ok(quote) => amount(*quote),
+            Err(e) => format!("Unavailable: {}", e),
+        },
+        "Amount Promised".green(),
+        order
+            .amount_promised
+            .map(amount)
+            .unwrap_or_else(|| "None (no winning bid)".to_string())
+    );
+    if order.amount_promised.is_some() {
+        result.push_str(&format!(
+            "\n  {}: {}\n  {}: {}\n  {}: {}",
+            "Over Min".green(),
+            format_bps(order.over_min_bps()),
+            "Vs Quote".green(),
+            format_bps(order.vs_quote_bps()),
+            "Quote Over Min".green(),
+            format_bps(order.quote_over_min_bps())
+        ));
+    }
+    result
+}
+
+fn optional(value: Option<u64>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Percentiles and histogram of one measure across the orders
+fn format_distribution(label: &str, mut values: Vec<i64>, bucket_bps: i64) -> String {
+    if values.is_empty() {
+        return format!("\n\n  {}: No orders", label.yellow());
+    }
+    values.sort_unstable();
+    let mean = values.iter().sum::<i64>() as f64 / values.len() as f64;
+    format!(
+        "\n\n  {}\n    {}: {}\n    {}: {:+.1} bps\n    {}: {}\n    {}: {}\n    {}: {}\n    {}: {} .. {}{}",
+        label.cyan(),
+        "Orders".green(),
+        values.len(),
+        "Mean".green(),
+        mean,
+        "Median".green(),
+        format_bps(Some(percentile(&values, 50))),
+        "P10".green(),
+        format_bps(Some(percentile(&values, 10))),
+        "P90".green(),
+        format_bps(Some(percentile(&values, 90))),
+        "Range".green(),
+        format_bps(values.first().copied()),
+        format_bps(values.last().copied()),
+        histogram(&values, bucket_bps, " bps")
+    )
+}
+
+/// Distribution of the improvement over the minimum and the quote across the
+/// orders, with `bucket_bps` wide histogram buckets
+pub fn format_efficiency_summary(orders: &[OrderEfficiency], bucket_bps: i64) -> String {
+    let won = orders
+        .iter()
+        .filter(|order| order.amount_promised.is_some())
+        .count();
+    let over_min: Vec<i64> = orders.iter().filter_map(|o| o.over_min_bps()).collect();
+    let vs_quote: Vec<i64> = orders.iter().filter_map(|o| o.vs_quote_bps()).collect();
+    let met_quote = vs_quote.iter().filter(|bps| **bps >= 0).count();
+
+    let mut result = format!(
+        "{}: {} of {} orders with a winning bid",
+        "Efficiency Summary".green(),
+        won,
+        orders.len()
+    );
+    result.push_str(&format_distribution("Over Min", over_min, bucket_bps));
+    let quoted = vs_quote.len();
+    result.push_str(&format_distribution("Vs Quote", vs_quote, bucket_bps));
+    if quoted > 0 {
+        result.push_str(&format!(
+            "\n    {}: {} of {} orders",
+            "Met Quote".green(),
+            met_quote,
+            quoted
+        ));
+    }
+    result
+}
@@ -0,0 +1,274 @@
+use crate::programs;
+use serde::Serialize;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+
+/// A transaction error narrowed down to the failing instruction and, for
+/// custom program errors, the Anchor error code with its name and message
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedError {
+    pub instruction_index: Option<u8>,
+    pub code: Option<u32>,
+    pub name: Option<String>,
+    pub message: String,
+}
+
+/// Anchor framework error codes (below 6000; program errors start at 6000)
+fn anchor_framework_error(code: u32) -> Option<(&'static str, &'static str)> {
+    let error = match code {
+        100 => (
+            "InstructionMissing",
+            "8 byte instruction identifier not provided",
+        ),
+        101 => (
+            "InstructionFallbackNotFound",
+            "Fallback functions are not supported",
+        ),
+        102 => (
+            "InstructionDidNotDeserialize",
+            "The program could not deserialize the given instruction",
+        ),
+        103 => (
+            "InstructionDidNotSerialize",
+            "The program could not serialize the given instruction",
+        ),
+        2000 => ("ConstraintMut", "A mut constraint was violated"),
+        2001 => ("ConstraintHasOne", "A has one constraint was violated"),
+        2002 => ("ConstraintSigner", "A signer constraint was violated"),
+        2003 => ("ConstraintRaw", "A raw constraint was violated"),
+        2004 => ("ConstraintOwner", "An owner constraint was violated"),
+        2005 => (
+            "ConstraintRentExempt",
+            "A rent exemption constraint was violated",
+        ),
+        2006 => ("ConstraintSeeds", "A seeds constraint was violated"),
+        2007 => (
+            "ConstraintExecutable",
+            "An executable constraint was violated",
+        ),
+        2009 => (
+            "ConstraintAssociated",
+            "An associated constraint was violated",
+        ),
+        2011 => ("ConstraintClose", "A close constraint was violated"),
+        2012 => ("ConstraintAddress", "An address constraint was violated"),
+        2014 => (
+            "ConstraintTokenMint",
+            "A token mint constraint was violated",
+        ),
+        2015 => (
+            "ConstraintTokenOwner",
+            "A token owner constraint was violated",
+        ),
+        3000 => (
+            "AccountDiscriminatorAlreadySet",
+            "The account discriminator was already set on this account",
+        ),
+        3001 => (
+            "AccountDiscriminatorNotFound",
+            "No 8 byte discriminator was found on the account",
+        ),
+        3002 => (
+            "AccountDiscriminatorMismatch",
+            "8 byte discriminator did not match what was expected",
+        ),
+        3003 => (
+            "AccountDidNotDeserialize",
+            "Failed to deserialize the account",
+        ),
+        3004 => ("AccountDidNotSerialize", "Failed to serialize the account"),
+        3005 => (
+            "AccountNotEnoughKeys",
+            "Not enough account keys given to the instruction",
+        ),
+        3006 => ("AccountNotMutable", "The given account is not mutable"),
+        3007 => (
+            "AccountOwnedByWrongProgram",
+            "The given account is owned by a different program than expected",
+        ),
+        3008 => ("InvalidProgramId", "Program ID was not as expected"),
+        3009 => (
+            "InvalidProgramExecutable",
+            "Program account is not executable",
+        ),
+        3010 => ("AccountNotSigner", "The given account did not sign"),
+        3011 => (
+            "AccountNotSystemOwned",
+            "The given account is not owned by the system program",
+        ),
+        3012 => (
+            "AccountNotInitialized",
+            "The program expected this account to be already initialized",
+        ),
+        3013 => (
+            "AccountNotProgramData",
+            "The given account is not the program data account",
+        ),
+        3014 => (
+            "AccountNotAssociatedTokenAccount",
+            "The given account is not the associated token account",
+        ),
+        3015 => (
+            "AccountSysvarMismatch",
+            "The given public key does not match the required sysvar",
+        ),
+        3016 => (
+            "AccountReallocExceedsLimit",
+            "The account reallocation exceeds the MAX_PERMITTED_DATA_INCREASE limit",
+        ),
+        3017 => (
+            "AccountDuplicateReallocs",
+            "The account was duplicated for more than one reallocation",
+        ),
+        _ => return None,
+    };
+    Some(error)
+}
+
+/// Swift auction program error codes, for when the `AnchorError` log line is
+/// missing (logs are truncated on long transactions)
+fn swift_auction_error(code: u32) -> Option<(&'static str, &'static str)> {
+    let error = match code {
+        6000 => ("AuctionClosed", "Auction is closed"),
+        6001 => ("BidTooLow", "Bid amount is too low"),
+        6002 => ("AuctionNotStarted", "Auction is not open for bids yet"),
+        6003 => ("AuctionNotEnded", "Auction has not ended yet"),
+        6004 => ("InvalidOrderHash", "Order does not match the auction hash"),
+        6005 => (
+            "AmountOutMinNotMet",
+            "Bid is below the order's minimum amount out",
+        ),
+        6006 => ("InvalidDriver", "Driver is not allowed to bid"),
+        6007 => ("AuctionAlreadyPosted", "Auction result was already posted"),
+        _ => return None,
+    };
+    Some(error)
+}
+
+/// Program whose invocation failed, from its `Program X failed: ...` log line
+fn failed_program(logs: &[String]) -> Option<&str> {
+    logs.iter()
+        .rev()
+        .find(|log| log.starts_with("Program ") && log.contains(" failed"))
+        .and_then(|log| log.split_whitespace().nth(1))
+}
+
+/// Log lines of the program invocation that failed, from its `invoke` line to
+/// its `failed` line, leaving out compute unit accounting
+pub fn failure_logs(logs: &[String]) -> Vec<String> {
+    let Some(end) = logs
+        .iter()
+        .rposition(|log| log.starts_with("Program ") && log.contains(" failed"))
+    else {
+        return Vec::new();
+    };
+    let invoke = match logs[end].split_whitespace().nth(1) {
+        Some(program) => format!("Program {} invoke", program),
+        None => return vec![logs[end].clone()],
+    };
+    let start = logs[..end]
+        .iter()
+        .rposition(|log| log.starts_with(&invoke))
+        .unwrap_or(end);
+    logs[start..=end]
+        .iter()
+        .filter(|log| !log.contains(" consumed "))
+        .cloned()
+        .collect()
+}
+
+/// Parse Anchor's `AnchorError ... Error Code: X. Error Number: N. Error Message: M.`
+/// log line, which carries the name and message of program-defined errors
+fn anchor_error_from_logs(logs: &[String], code: u32) -> Option<(String, String)> {
+    logs.iter().find_map(|log| {
+        let rest = log.split("AnchorError").nth(1)?;
+        let name = rest.split("Error Code: ").nth(1)?.split('.').next()?;
+        let number = rest.split("Error Number: ").nth(1)?.split('.').next()?;
+        if number.trim().parse::<u32>().ok()? != code {
+            return None;
+        }
+        let message = rest
+            .split("Error Message: ")
+            .nth(1)
+            .map(|m| m.trim_end_matches('.').to_string())
+            .unwrap_or_default();
+        Some((name.to_string(), message))
+    })
+}
+
+pub fn decode_transaction_error(err: &TransactionError, logs: &[String]) -> DecodedError {
+    let (instruction_index, instruction_error) = match err {
+        TransactionError::InstructionError(index, instruction_error) => {
+            (Some(*index), instruction_error)
+        }
+        other => {
+            return DecodedError {
+                instruction_index: None,
+                code: None,
+                name: None,
+                message: other.to_string(),
+            }
+        }
+    };
+
+    let code = match instruction_error {
+        InstructionError::Custom(code) => *code,
+        other => {
+            return DecodedError {
+                instruction_index,
+                code: None,
+                name: None,
+                message: other.to_string(),
+            }
+        }
+    };
+
+    let auction_program = programs::auction_program_id().to_string();
+    let known = if code >= 6000 {
+        failed_program(logs)
+            .filter(|program| *program == auction_program)
+            .and_then(|_| swift_auction_error(code))
+    } else {
+        anchor_framework_error(code)
+    };
+    let (name, message) = match anchor_error_from_logs(logs, code) {
+        Some((name, message)) => (Some(name), message),
+        None => match known {
+            Some((name, message)) => (Some(name.to_string()), message.to_string()),
+            None => (None, format!("Custom program error: {:#x}", code)),
+        },
+    };
+
+    DecodedError {
+        instruction_index,
+        code: Some(code),
+        name,
+        message,
+    }
+}
+
+pub fn format_decoded_error(error: &DecodedError) -> String {
+    let mut parts = Vec::new();
+    if let Some(index) = error.instruction_index {
+        // Shown 1-based, matching the instruction numbering of decode-tx
+        parts.push(format!("instruction {}", index as u16 + 1));
+    }
+    if let Some(code) = error.code {
+        parts.push(format!("code {}", code));
+    }
+    let location = if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    };
+
+    match &error.name {
+        Some(name) => format!("{}: {}{}", name, error.message, location),
+        None => format!("{}{}", error.message, location),
+    }
+}
+
+/// Short reason of a failure, for grouping: the error name when known
+pub fn failure_reason(error: &DecodedError) -> String {
+    error.name.clone().unwrap_or_else(|| error.message.clone())
+}
@@ -0,0 +1,175 @@
+use crate::decoder::{self, ArgType, KNOWN_PROGRAMS};
+use crate::programs;
+use base64::Engine;
+use colored::*;
+use solana_sdk::pubkey::Pubkey;
+
+pub struct EventDef {
+    pub program_id: fn() -> Pubkey,
+    pub name: &'static str,
+    pub fields: &'static [(&'static str, ArgType)],
+}
+
+pub const KNOWN_EVENTS: &[EventDef] = &[
+    EventDef {
+        program_id: programs::auction_program_id,
+        name: "AuctionBid",
+        fields: &[
+            ("auction", ArgType::Pubkey),
+            ("order_hash", ArgType::Bytes32),
+            ("driver", ArgType::Pubkey),
+            ("amount_bid", ArgType::U64),
+        ],
+    },
+    EventDef {
+        program_id: programs::auction_program_id,
+        name: "AuctionClosed",
+        fields: &[
+            ("auction", ArgType::Pubkey),
+            ("order_hash", ArgType::Bytes32),
+            ("winner", ArgType::Pubkey),
+            ("amount_promised", ArgType::U64),
+        ],
+    },
+    EventDef {
+        program_id: programs::swift_program_id,
+        name: "OrderInitialized",
+        fields: &[
+            ("order_hash", ArgType::Bytes32),
+            ("trader", ArgType::Pubkey),
+            ("amount_in", ArgType::U64),
+        ],
+    },
+    EventDef {
+        program_id: programs::swift_program_id,
+        name: "OrderFulfilled",
+        fields: &[
+            ("order_hash", ArgType::Bytes32),
+            ("driver", ArgType::Pubkey),
+            ("amount_out", ArgType::U64),
+        ],
+    },
+    EventDef {
+        program_id: programs::swift_program_id,
+        name: "OrderSettled",
+        fields: &[
+            ("order_hash", ArgType::Bytes32),
+            ("amount_out", ArgType::U64),
+        ],
+    },
+    EventDef {
+        program_id: programs::swift_program_id,
+        name: "OrderUnlocked",
+        fields: &[("order_hash", ArgType::Bytes32)],
+    },
+    EventDef {
+        program_id: programs::swift_program_id,
+        name: "OrderRefunded",
+        fields: &[("order_hash", ArgType::Bytes32)],
+    },
+];
+
+#[derive(Debug, Clone)]
+pub struct DecodedEvent {
+    /// Program whose invocation logged the event
+    pub program_id: String,
+    pub name: Option<String>,
+    pub fields: Vec<(String, String)>,
+    /// Event data that was not decoded into fields
+    pub remaining_data: Vec<u8>,
+}
+
+/// Anchor event discriminator: first 8 bytes of sha256("event:<Name>")
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    let hash = solana_sdk::hash::hash(format!("event:{}", name).as_bytes());
+    hash.to_bytes()[..8].try_into().unwrap()
+}
+
+pub fn decode_event(program_id: &str, data: &[u8]) -> DecodedEvent {
+    let mut decoded = DecodedEvent {
+        program_id: program_id.to_string(),
+        name: None,
+        fields: Vec::new(),
+        remaining_data: data.to_vec(),
+    };
+    if data.len() < 8 {
+        return decoded;
+    }
+
+    let Some(event) = KNOWN_EVENTS.iter().find(|event| {
+        (event.program_id)().to_string() == program_id
+            && event_discriminator(event.name) == data[..8]
+    }) else {
+        return decoded;
+    };
+
+    let mut rest = &data[8..];
+    decoded.name = Some(event.name.to_string());
+    decoded.fields = decoder::decode_args(&mut rest, event.fields);
+    decoded.remaining_data = rest.to_vec();
+    decoded
+}
+
+/// Decode the `Program data: <base64>` lines of a transaction's logs. The
+/// invoke/success/failed lines are tracked so that each event is attributed
+/// to the program that emitted it, including from inner instructions.
+pub fn decode_events_from_logs(logs: &[String]) -> Vec<DecodedEvent> {
+    let mut stack: Vec<String> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            let Some(program_id) = stack.last() else {
+                continue;
+            };
+            if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data.trim()) {
+                events.push(decode_event(program_id, &bytes));
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut parts = rest.split_whitespace();
+            let (Some(program_id), Some(action)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            match action {
+                "invoke" => stack.push(program_id.to_string()),
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
+
+fn program_name(program_id: &str) -> Option<&'static str> {
+    KNOWN_PROGRAMS
+        .iter()
+        .find(|program| (program.id)().to_string() == program_id)
+        .map(|program| program.name)
+}
+
+pub fn format_events(events: &[DecodedEvent], indent: &str) -> String {
+    let mut result = String::new();
+    for event in events {
+        let title = match (program_name(&event.program_id), &event.name) {
+            (Some(program), Some(name)) => format!("{}: {}", program, name),
+            (Some(program), None) => format!("{}: unknown event", program),
+            (None, _) => format!("{}: unknown event", event.program_id),
+        };
+        result.push_str(&format!("\n{}{}: {}", indent, "Event".cyan(), title));
+        for (name, value) in &event.fields {
+            result.push_str(&format!("\n{}  {}: {}", indent, name, value));
+        }
+        if !event.remaining_data.is_empty() {
+            result.push_str(&format!(
+                "\n{}  {}: {}",
+                indent,
+                "Data".green(),
+                hex::encode(&event.remaining_data)
+            ));
+        }
+    }
+    result
+}
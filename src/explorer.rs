@@ -0,0 +1,46 @@
+use crate::transport::HttpApi;
+use anyhow::{Context, Result};
+use std::sync::Arc;
+
+/// Client of the Mayan explorer API
+pub struct ExplorerApi {
+    http: Arc<dyn HttpApi>,
+    /// `None` on clusters the explorer does not index
+    base_url: Option<String>,
+}
+
+impl ExplorerApi {
+    pub fn new(http: Arc<dyn HttpApi>, base_url: Option<String>) -> Self {
+        ExplorerApi { http, base_url }
+    }
+
+    /// Fetch the full explorer record of an order
+    pub async fn order_record(&self, order_id: &str) -> Result<serde_json::Value> {
+        let base_url = self.base_url.as_deref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "No explorer API for this cluster to look up order {}; pass an auction state address or --explorer-api",
+                order_id
+            )
+        })?;
+        let url = format!(
+            "{}/v3/swap/order-id/{}",
+            base_url.trim_end_matches('/'),
+            order_id
+        );
+
+        let response = self
+            .http
+            .get(&url)
+            .await
+            .context("Failed to send request to Mayan API")?;
+
+        if !response.is_success() {
+            return Err(anyhow::anyhow!(
+                "API request failed with status: {}",
+                response.status
+            ));
+        }
+
+        response.json().context("Failed to parse JSON response")
+    }
+}
@@ -0,0 +1,339 @@
+use crate::cursor::SignatureCursor;
+use crate::decoder::{self, account, arg, auction_instructions, DecodedTransaction};
+use crate::programs;
+use crate::rpc::Rpc;
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub struct ExporterOptions {
+    pub listen: String,
+    /// Accounts watched besides the auction program: auction states, solver
+    /// wallets or anything else whose transactions carry bids
+    pub watch: Vec<Pubkey>,
+    pub interval: u64,
+    /// Signatures read per account on the first poll
+    pub recent: usize,
+    /// Auctions kept for the per-auction metrics, most recently active first
+    pub max_auctions: usize,
+}
+
+#[derive(Default)]
+struct AuctionStats {
+    bids: u64,
+    failed_bids: u64,
+    /// From the order of any bid; it is the same in every bid of the auction
+    amount_out_min: Option<u64>,
+    /// Highest successful bid and its driver, which is the amount promised so
+    /// far since the auction program only accepts higher bids
+    leader: Option<(u64, String)>,
+    closed: bool,
+    last_slot: u64,
+}
+
+impl AuctionStats {
+    fn winning_margin(&self) -> Option<(u64, u64)> {
+        let amount_out_min = self.amount_out_min?;
+        let (amount_promised, _) = self.leader.as_ref()?;
+        Some((
+            amount_out_min,
+            amount_promised.saturating_sub(amount_out_min),
+        ))
+    }
+}
+
+#[derive(Default)]
+struct Metrics {
+    auctions: HashMap<String, AuctionStats>,
+    auctions_seen: u64,
+    bids: u64,
+    failed_bids: u64,
+    solver_wins: BTreeMap<String, u64>,
+    poll_errors: u64,
+    last_poll: Option<i64>,
+}
+
+impl Metrics {
+    fn auction(&mut self, address: &str) -> &mut AuctionStats {
+        if !self.auctions.contains_key(address) {
+            self.auctions_seen += 1;
+        }
+        self.auctions.entry(address.to_string()).or_default()
+    }
+
+    fn record_transaction(&mut self, transaction: &DecodedTransaction) {
+        let auction_program = programs::auction_program_id().to_string();
+        let mut instructions = Vec::new();
+        auction_instructions(
+            &transaction.instructions,
+            &auction_program,
+            &mut instructions,
+        );
+        let failed = transaction.err.is_some();
+
+        for instruction in instructions {
+            let Some(address) = account(instruction, "auction_state") else {
+                continue;
+            };
+            match instruction.name.as_deref() {
+                Some("bid") => {
+                    let driver = account(instruction, "driver")
+                        .unwrap_or_default()
+                        .to_string();
+                    let amount = arg(instruction, "amount_bid").and_then(|a| a.parse().ok());
+                    let amount_out_min =
+                        arg(instruction, "order.amount_out_min").and_then(|a| a.parse().ok());
+                    self.bids += 1;
+                    if failed {
+                        self.failed_bids += 1;
+                    }
+
+                    let auction = self.auction(address);
+                    auction.bids += 1;
+                    auction.last_slot = auction.last_slot.max(transaction.slot);
+                    auction.amount_out_min = auction.amount_out_min.or(amount_out_min);
+                    if failed {
+                        auction.failed_bids += 1;
+                    } else if let Some(amount) = amount {
+                        if auction
+                            .leader
+                            .as_ref()
+                            .is_none_or(|(best, _)| amount > *best)
+                        {
+                            auction.leader = Some((amount, driver));
+                        }
+                    }
+                }
+                Some("close_auction") if !failed => {
+                    // The AuctionClosed event names the winner; otherwise it is
+                    // the highest bid seen
+                    let winner = transaction
+                        .events
+                        .iter()
+                        .find(|event| event.name.as_deref() == Some("AuctionClosed"))
+                        .and_then(|event| {
+                            event
+                                .fields
+                                .iter()
+                                .find(|(field, _)| field == "winner")
+                                .map(|(_, winner)| winner.clone())
+                        });
+                    let auction = self.auction(address);
+                    auction.last_slot = auction.last_slot.max(transaction.slot);
+                    if auction.closed {
+                        continue;
+                    }
+                    auction.closed = true;
+                    let winner =
+                        winner.or(auction.leader.as_ref().map(|(_, driver)| driver.clone()));
+                    if let Some(winner) = winner {
+                        *self.solver_wins.entry(winner).or_default() += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Keep the most recently active auctions, bounding the label cardinality
+    fn prune(&mut self, max_auctions: usize) {
+        if self.auctions.len() <= max_auctions {
+            return;
+        }
+        let mut slots: Vec<u64> = self.auctions.values().map(|a| a.last_slot).collect();
+        slots.sort_unstable_by(|a, b| b.cmp(a));
+        let cutoff = slots[max_auctions - 1];
+        self.auctions
+            .retain(|_, auction| auction.last_slot >= cutoff);
+    }
+
+    /// Prometheus text exposition format
+    fn render(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            for (labels, value) in samples {
+                let _ = writeln!(out, "{}{} {}", name, labels, value);
+            }
+        };
+        let auction_label = |address: &str| format!("{{auction=\"{}\"}}", address);
+        let mut auctions: Vec<(&String, &AuctionStats)> = self.auctions.iter().collect();
+        auctions.sort_by(|a, b| a.0.cmp(b.0));
+
+        metric(
+            "mayan_auctions_seen_total",
+            "counter",
+            "Auctions seen since the exporter started",
+            vec![(String::new(), self.auctions_seen.to_string())],
+        );
+        metric(
+            "mayan_bids_total",
+            "counter",
+            "Bids seen since the exporter started",
+            vec![(String::new(), self.bids.to_string())],
+        );
+        metric(
+            "mayan_failed_bids_total",
+            "counter",
+            "Bids whose transaction failed",
+            vec![(String::new(), self.failed_bids.to_string())],
+        );
+        let ratio = if self.bids > 0 {
+            self.failed_bids as f64 / self.bids as f64
+        } else {
+            0.0
+        };
+        metric(
+            "mayan_failed_bid_ratio",
+            "gauge",
+            "Failed bids over all bids seen",
+            vec![(String::new(), ratio.to_string())],
+        );
+        metric(
+            "mayan_auction_bids",
+            "gauge",
+            "Bids per auction",
+            auctions
+                .iter()
+                .map(|(address, auction)| (auction_label(address), auction.bids.to_string()))
+                .collect(),
+        );
+        metric(
+            "mayan_auction_failed_bids",
+            "gauge",
+            "Failed bids per auction",
+            auctions
+                .iter()
+                .map(|(address, auction)| (auction_label(address), auction.failed_bids.to_string()))
+                .collect(),
+        );
+        metric(
+            "mayan_auction_winning_margin",
+            "gauge",
+            "Amount promised by the winning bid over amount_out_min, in normalized units",
+            auctions
+                .iter()
+                .filter_map(|(address, auction)| {
+                    let (_, margin) = auction.winning_margin()?;
+                    Some((auction_label(address), margin.to_string()))
+                })
+                .collect(),
+        );
+        metric(
+            "mayan_auction_winning_margin_ratio",
+            "gauge",
+            "Winning margin as a fraction of amount_out_min",
+            auctions
+                .iter()
+                .filter_map(|(address, auction)| {
+                    let (amount_out_min, margin) = auction.winning_margin()?;
+                    (amount_out_min > 0).then(|| {
+                        (
+                            auction_label(address),
+                            (margin as f64 / amount_out_min as f64).to_string(),
+                        )
+                    })
+                })
+                .collect(),
+        );
+        metric(
+            "mayan_solver_wins_total",
+            "counter",
+            "Closed auctions won per solver",
+            self.solver_wins
+                .iter()
+                .map(|(solver, wins)| (format!("{{solver=\"{}\"}}", solver), wins.to_string()))
+                .collect(),
+        );
+        metric(
+            "mayan_exporter_poll_errors_total",
+            "counter",
+            "Polls that failed to read an account",
+            vec![(String::new(), self.poll_errors.to_string())],
+        );
+        if let Some(last_poll) = self.last_poll {
+            metric(
+                "mayan_exporter_last_poll_timestamp_seconds",
+                "gauge",
+                "Unix time of the last completed poll",
+                vec![(String::new(), last_poll.to_string())],
+            );
+        }
+        out
+    }
+}
+
+fn poll(
+    rpc: &Rpc,
+    options: &ExporterOptions,
+    cursor: &mut SignatureCursor,
+    metrics: &Mutex<Metrics>,
+) {
+    let mut addresses = vec![programs::auction_program_id()];
+    addresses.extend(options.watch.iter().copied());
+
+    for address in &addresses {
+        let transactions = cursor.read_new(rpc, address, options.recent, |signature| {
+            decoder::decode_transaction(&decoder::fetch_transaction(rpc, signature)?)
+        });
+        match transactions {
+            Ok(transactions) => {
+                let mut metrics = metrics.lock().unwrap();
+                for transaction in &transactions {
+                    metrics.record_transaction(transaction);
+                }
+            }
+            Err(e) => {
+                eprintln!("Warning: failed to poll {}: {:#}", address, e);
+                metrics.lock().unwrap().poll_errors += 1;
+            }
+        }
+    }
+
+    let mut metrics = metrics.lock().unwrap();
+    metrics.prune(options.max_auctions);
+    metrics.last_poll = Some(chrono::Utc::now().timestamp());
+}
+
+async fn metrics_handler(State(metrics): State<Arc<Mutex<Metrics>>>) -> impl IntoResponse {
+    let body = metrics.lock().unwrap().render();
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+/// Poll the auction program and watched accounts every `interval` seconds and
+/// serve the metrics on `/metrics` until the process is stopped
+pub async fn run(rpc: Rpc, options: ExporterOptions) -> Result<()> {
+    let metrics = Arc::new(Mutex::new(Metrics::default()));
+
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(metrics.clone());
+    let listener = tokio::net::TcpListener::bind(&options.listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", options.listen))?;
+    eprintln!(
+        "Serving metrics on http://{}/metrics",
+        listener.local_addr()?
+    );
+
+    tokio::spawn(async move {
+        let mut cursor = SignatureCursor::default();
+        loop {
+            poll(&rpc, &options, &mut cursor, &metrics);
+            tokio::time::sleep(Duration::from_secs(options.interval)).await;
+        }
+    });
+
+    axum::serve(listener, app)
+        .await
+        .context("HTTP server failed")
+}
@@ -0,0 +1,191 @@
+use crate::BidEntry;
+use colored::*;
+use solana_transaction_status::{UiInstruction, UiParsedInstruction};
+use std::collections::BTreeMap;
+
+/// Base fee charged per transaction signature, in lamports
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+/// Accounts Jito block engines take tips on; a transfer to one of them means
+/// the transaction was sent in a bundle
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// ComputeBudget instruction tags
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// What a bid transaction asked for and paid to land
+#[derive(Debug, Clone, Default)]
+pub struct BidFees {
+    /// From `SetComputeUnitLimit`
+    pub unit_limit: Option<u32>,
+    /// From `SetComputeUnitPrice`, in micro-lamports per compute unit
+    pub unit_price: Option<u64>,
+    pub units_consumed: Option<u64>,
+    /// Total fee paid, in lamports
+    pub fee: u64,
+    pub signatures: usize,
+    /// SOL transferred to Jito tip accounts, in lamports; paid on top of `fee`
+    pub jito_tip: u64,
+}
+
+impl BidFees {
+    /// Apply a ComputeBudget instruction's data; other instructions are ignored
+    pub fn apply_compute_budget(&mut self, data: &[u8]) {
+        match data.split_first() {
+            Some((&SET_COMPUTE_UNIT_LIMIT, rest)) if rest.len() >= 4 => {
+                self.unit_limit = Some(u32::from_le_bytes(rest[..4].try_into().unwrap()));
+            }
+            Some((&SET_COMPUTE_UNIT_PRICE, rest)) if rest.len() >= 8 => {
+                self.unit_price = Some(u64::from_le_bytes(rest[..8].try_into().unwrap()));
+            }
+            _ => {}
+        }
+    }
+
+    /// Add a System transfer to a Jito tip account; other instructions are
+    /// ignored. Takes `jsonParsed` instructions, top-level or inner.
+    pub fn apply_transfer(&mut self, instruction: &UiInstruction) {
+        let UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) = instruction else {
+            return;
+        };
+        if parsed.program != "system"
+            || parsed.parsed.get("type").and_then(|t| t.as_str()) != Some("transfer")
+        {
+            return;
+        }
+        let Some(info) = parsed.parsed.get("info") else {
+            return;
+        };
+        let destination = info.get("destination").and_then(|d| d.as_str());
+        if destination.is_some_and(|destination| JITO_TIP_ACCOUNTS.contains(&destination)) {
+            self.jito_tip += info
+                .get("lamports")
+                .and_then(|lamports| lamports.as_u64())
+                .unwrap_or(0);
+        }
+    }
+
+    /// Fee paid above the base signature fee, in lamports
+    pub fn priority_fee(&self) -> u64 {
+        self.fee
+            .saturating_sub(LAMPORTS_PER_SIGNATURE * self.signatures as u64)
+    }
+
+    /// Priority fee over the compute units actually consumed, in micro-lamports
+    /// per unit; lower than the unit price when the limit was set too high
+    pub fn effective_unit_price(&self) -> Option<u64> {
+        let units = self.units_consumed.filter(|units| *units > 0)?;
+        Some((self.priority_fee() as u128 * 1_000_000 / units as u128) as u64)
+    }
+}
+
+fn optional(value: Option<impl ToString>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Lines of a bid's fee breakdown, indented under the bid
+pub fn format_bid_fees(fees: &BidFees) -> String {
+    let units = match fees.unit_limit {
+        Some(limit) => format!("{} of {}", optional(fees.units_consumed), limit),
+        None => optional(fees.units_consumed),
+    };
+    let mut result = format!(
+        "\n  {}: {}\n  {}: {}\n  {}: {} lamports ({} priority)\n  {}: {}",
+        "Compute Units".green(),
+        units,
+        "Unit Price".green(),
+        fees.unit_price
+            .map(|price| format!("{} micro-lamports", price))
+            .unwrap_or_else(|| "None".to_string()),
+        "Fee".green(),
+        fees.fee,
+        fees.priority_fee(),
+        "Effective Unit Price".green(),
+        fees.effective_unit_price()
+            .map(|price| format!("{} micro-lamports", price))
+            .unwrap_or_else(|| "Unknown".to_string())
+    );
+    if fees.jito_tip > 0 {
+        result.push_str(&format!(
+            "\n  {}: {} lamports (bundle)",
+            "Jito Tip".green(),
+            fees.jito_tip
+        ));
+    }
+    result
+}
+
+fn median(values: &mut [u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    Some(values[values.len() / 2])
+}
+
+/// Average fees and median unit price of each bidder's bids
+pub fn format_fee_summary(bids: &[BidEntry]) -> String {
+    if bids.is_empty() {
+        return String::new();
+    }
+    let mut by_bidder: BTreeMap<&str, Vec<&BidFees>> = BTreeMap::new();
+    for bid in bids {
+        by_bidder
+            .entry(bid.bidder.as_str())
+            .or_default()
+            .push(&bid.fees);
+    }
+
+    let mut result = format!("{}:", "Fees by Bidder".green());
+    for (bidder, fees) in by_bidder {
+        let count = fees.len() as u64;
+        let total: u64 = fees.iter().map(|fees| fees.fee).sum();
+        let priority: u64 = fees.iter().map(|fees| fees.priority_fee()).sum();
+        let mut prices: Vec<u64> = fees.iter().filter_map(|fees| fees.unit_price).collect();
+        let mut units: Vec<u64> = fees.iter().filter_map(|fees| fees.units_consumed).collect();
+        result.push_str(&format!(
+            "\n  {}\n    {}: {}\n    {}: {} lamports ({} average)\n    {}: {} lamports average\n    {}: {}\n    {}: {}",
+            bidder.cyan(),
+            "Bids".green(),
+            count,
+            "Fees".green(),
+            total,
+            total / count,
+            "Priority Fees".green(),
+            priority / count,
+            "Median Unit Price".green(),
+            median(&mut prices)
+                .map(|price| format!("{} micro-lamports", price))
+                .unwrap_or_else(|| "None".to_string()),
+            "Median Compute Units".green(),
+            optional(median(&mut units))
+        ));
+        let tipped: Vec<u64> = fees
+            .iter()
+            .map(|fees| fees.jito_tip)
+            .filter(|tip| *tip > 0)
+            .collect();
+        if !tipped.is_empty() {
+            result.push_str(&format!(
+                "\n    {}: {} of {} bids, {} lamports average",
+                "Jito Tips".green(),
+                tipped.len(),
+                count,
+                tipped.iter().sum::<u64>() / tipped.len() as u64
+            ));
+        }
+    }
+    result
+}
@@ -0,0 +1,600 @@
+use crate::rpc::Rpc;
+use anyhow::{Context, Result};
+use flate2::read::ZlibDecoder;
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::io::Read;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Nesting depth at which decoding gives up, so a recursive type cannot loop
+const MAX_DEPTH: usize = 32;
+
+/// Field names with their decoded values, in declaration order
+pub type DecodedFields = Vec<(String, String)>;
+
+/// A type in an Anchor IDL
+#[derive(Debug, Clone)]
+pub enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    F32,
+    F64,
+    String,
+    Bytes,
+    Pubkey,
+    Option(Box<IdlType>),
+    /// Like `Option`, with a 4-byte tag
+    COption(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Defined(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum IdlFields {
+    Named(Vec<(String, IdlType)>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Debug, Clone)]
+pub enum IdlTypeDef {
+    Struct(IdlFields),
+    Enum(Vec<(String, Option<IdlFields>)>),
+    Alias(IdlType),
+}
+
+#[derive(Debug, Clone)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    /// Account names in order; accounts of nested groups are prefixed with the
+    /// group name
+    pub accounts: Vec<String>,
+    pub args: Vec<(String, IdlType)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IdlAccount {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+}
+
+/// An Anchor IDL, in either the legacy format or the format of Anchor 0.30 and
+/// later (with explicit discriminators)
+#[derive(Debug, Clone)]
+pub struct Idl {
+    /// Leaked once per IDL so decoded instructions can name their program like
+    /// the built-in ones; IDLs are loaded once and kept for the whole run
+    pub name: &'static str,
+    pub address: Option<String>,
+    pub instructions: Vec<IdlInstruction>,
+    pub accounts: Vec<IdlAccount>,
+    pub types: HashMap<String, IdlTypeDef>,
+}
+
+fn sighash(namespace: &str, name: &str) -> Vec<u8> {
+    let hash = solana_sdk::hash::hash(format!("{}:{}", namespace, name).as_bytes());
+    hash.to_bytes()[..8].to_vec()
+}
+
+/// Legacy IDLs name instructions in camelCase, but the discriminator hashes
+/// the Rust (snake_case) name
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Result<&'a str> {
+    value
+        .get(key)
+        .and_then(|field| field.as_str())
+        .ok_or_else(|| anyhow::anyhow!("IDL entry is missing \"{}\": {}", key, value))
+}
+
+fn discriminator(value: &Value) -> Option<Vec<u8>> {
+    value.get("discriminator")?.as_array().map(|bytes| {
+        bytes
+            .iter()
+            .filter_map(|byte| byte.as_u64().map(|byte| byte as u8))
+            .collect()
+    })
+}
+
+fn parse_type(value: &Value) -> Result<IdlType> {
+    if let Some(name) = value.as_str() {
+        return Ok(match name {
+            "bool" => IdlType::Bool,
+            "u8" => IdlType::U8,
+            "i8" => IdlType::I8,
+            "u16" => IdlType::U16,
+            "i16" => IdlType::I16,
+            "u32" => IdlType::U32,
+            "i32" => IdlType::I32,
+            "u64" => IdlType::U64,
+            "i64" => IdlType::I64,
+            "u128" => IdlType::U128,
+            "i128" => IdlType::I128,
+            "f32" => IdlType::F32,
+            "f64" => IdlType::F64,
+            "string" => IdlType::String,
+            "bytes" => IdlType::Bytes,
+            "publicKey" | "pubkey" => IdlType::Pubkey,
+            other => return Err(anyhow::anyhow!("Unsupported IDL type: {}", other)),
+        });
+    }
+    if let Some(inner) = value.get("option") {
+        return Ok(IdlType::Option(Box::new(parse_type(inner)?)));
+    }
+    if let Some(inner) = value.get("coption") {
+        return Ok(IdlType::COption(Box::new(parse_type(inner)?)));
+    }
+    if let Some(inner) = value.get("vec") {
+        return Ok(IdlType::Vec(Box::new(parse_type(inner)?)));
+    }
+    if let Some(array) = value.get("array").and_then(|array| array.as_array()) {
+        let len = array
+            .get(1)
+            .and_then(|len| len.as_u64())
+            .ok_or_else(|| anyhow::anyhow!("Unsupported IDL array length: {}", value))?;
+        let inner = array
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("IDL array without a type: {}", value))?;
+        return Ok(IdlType::Array(Box::new(parse_type(inner)?), len as usize));
+    }
+    if let Some(defined) = value.get("defined") {
+        // Legacy IDLs give the name, newer ones an object with the name and generics
+        let name = defined
+            .as_str()
+            .or_else(|| defined.get("name").and_then(|name| name.as_str()))
+            .ok_or_else(|| anyhow::anyhow!("Unsupported IDL defined type: {}", value))?;
+        return Ok(IdlType::Defined(name.to_string()));
+    }
+    Err(anyhow::anyhow!("Unsupported IDL type: {}", value))
+}
+
+fn parse_fields(fields: &[Value]) -> Result<IdlFields> {
+    if fields.iter().all(|field| field.get("name").is_some()) {
+        fields
+            .iter()
+            .map(|field| {
+                Ok((
+                    str_field(field, "name")?.to_string(),
+                    parse_type(&field["type"])?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(IdlFields::Named)
+    } else {
+        fields
+            .iter()
+            .map(parse_type)
+            .collect::<Result<Vec<_>>>()
+            .map(IdlFields::Tuple)
+    }
+}
+
+fn parse_type_def(value: &Value) -> Result<IdlTypeDef> {
+    match value.get("kind").and_then(|kind| kind.as_str()) {
+        Some("struct") => {
+            let fields = value
+                .get("fields")
+                .and_then(|fields| fields.as_array())
+                .map(|fields| parse_fields(fields))
+                .transpose()?
+                .unwrap_or(IdlFields::Named(Vec::new()));
+            Ok(IdlTypeDef::Struct(fields))
+        }
+        Some("enum") => {
+            let variants = value
+                .get("variants")
+                .and_then(|variants| variants.as_array())
+                .ok_or_else(|| anyhow::anyhow!("IDL enum without variants: {}", value))?;
+            variants
+                .iter()
+                .map(|variant| {
+                    let fields = variant
+                        .get("fields")
+                        .and_then(|fields| fields.as_array())
+                        .map(|fields| parse_fields(fields))
+                        .transpose()?;
+                    Ok((str_field(variant, "name")?.to_string(), fields))
+                })
+                .collect::<Result<Vec<_>>>()
+                .map(IdlTypeDef::Enum)
+        }
+        Some("type") | Some("alias") => {
+            let alias = value
+                .get("alias")
+                .or_else(|| value.get("value"))
+                .ok_or_else(|| anyhow::anyhow!("IDL alias without a type: {}", value))?;
+            Ok(IdlTypeDef::Alias(parse_type(alias)?))
+        }
+        _ => Err(anyhow::anyhow!(
+            "Unsupported IDL type definition: {}",
+            value
+        )),
+    }
+}
+
+/// Account names of an instruction, flattening nested account groups
+fn flatten_accounts(accounts: &[Value], prefix: &str, names: &mut Vec<String>) {
+    for account in accounts {
+        let name = account
+            .get("name")
+            .and_then(|name| name.as_str())
+            .unwrap_or_default();
+        let name = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        match account.get("accounts").and_then(|group| group.as_array()) {
+            Some(group) => flatten_accounts(group, &name, names),
+            None => names.push(name),
+        }
+    }
+}
+
+impl Idl {
+    pub fn parse(value: &Value) -> Result<Self> {
+        let name = value
+            .get("metadata")
+            .and_then(|metadata| metadata.get("name"))
+            .or_else(|| value.get("name"))
+            .and_then(|name| name.as_str())
+            .unwrap_or("IDL")
+            .to_string();
+        let address = value
+            .get("address")
+            .or_else(|| value.get("metadata").and_then(|m| m.get("address")))
+            .and_then(|address| address.as_str())
+            .map(String::from);
+
+        let mut types = HashMap::new();
+        for def in value
+            .get("types")
+            .and_then(|types| types.as_array())
+            .into_iter()
+            .flatten()
+        {
+            types.insert(
+                str_field(def, "name")?.to_string(),
+                parse_type_def(&def["type"])?,
+            );
+        }
+
+        let mut accounts = Vec::new();
+        for account in value
+            .get("accounts")
+            .and_then(|accounts| accounts.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let account_name = str_field(account, "name")?.to_string();
+            // Legacy IDLs define the account type inline rather than in `types`
+            if let Some(def) = account.get("type") {
+                types.insert(account_name.clone(), parse_type_def(def)?);
+            }
+            accounts.push(IdlAccount {
+                discriminator: discriminator(account)
+                    .unwrap_or_else(|| sighash("account", &account_name)),
+                name: account_name,
+            });
+        }
+
+        let mut instructions = Vec::new();
+        for instruction in value
+            .get("instructions")
+            .and_then(|instructions| instructions.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let instruction_name = str_field(instruction, "name")?.to_string();
+            let mut account_names = Vec::new();
+            if let Some(list) = instruction.get("accounts").and_then(|a| a.as_array()) {
+                flatten_accounts(list, "", &mut account_names);
+            }
+            let args = instruction
+                .get("args")
+                .and_then(|args| args.as_array())
+                .into_iter()
+                .flatten()
+                .map(|arg| {
+                    Ok((
+                        str_field(arg, "name")?.to_string(),
+                        parse_type(&arg["type"])?,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            instructions.push(IdlInstruction {
+                discriminator: discriminator(instruction)
+                    .unwrap_or_else(|| sighash("global", &to_snake_case(&instruction_name))),
+                name: instruction_name,
+                accounts: account_names,
+                args,
+            });
+        }
+
+        Ok(Idl {
+            name: Box::leak(name.into_boxed_str()),
+            address,
+            instructions,
+            accounts,
+            types,
+        })
+    }
+
+    pub fn instruction(&self, data: &[u8]) -> Option<&IdlInstruction> {
+        self.instructions.iter().find(|instruction| {
+            !instruction.discriminator.is_empty() && data.starts_with(&instruction.discriminator)
+        })
+    }
+
+    /// Decode instruction arguments in order, stopping at the first one that
+    /// does not fit; `data` is advanced past the decoded arguments
+    pub fn decode_args(&self, instruction: &IdlInstruction, data: &mut &[u8]) -> DecodedFields {
+        let mut decoded = Vec::new();
+        for (name, ty) in &instruction.args {
+            let mut attempt = *data;
+            let mut fields = Vec::new();
+            match self.decode_value(ty, &mut attempt, name, &mut fields, 0) {
+                Ok(()) => {
+                    *data = attempt;
+                    decoded.extend(fields);
+                }
+                Err(_) => break,
+            }
+        }
+        decoded
+    }
+
+    /// The account type of `data`, by its discriminator, with its decoded fields
+    /// and any bytes left after them
+    pub fn decode_account(&self, data: &[u8]) -> Option<(String, Result<DecodedFields>)> {
+        let account = self.accounts.iter().find(|account| {
+            !account.discriminator.is_empty() && data.starts_with(&account.discriminator)
+        })?;
+        let mut rest = &data[account.discriminator.len()..];
+        let mut fields = Vec::new();
+        let result = self
+            .decode_value(
+                &IdlType::Defined(account.name.clone()),
+                &mut rest,
+                "",
+                &mut fields,
+                0,
+            )
+            .map(|()| fields);
+        Some((account.name.clone(), result))
+    }
+
+    fn decode_fields(
+        &self,
+        fields: &IdlFields,
+        data: &mut &[u8],
+        path: &str,
+        out: &mut DecodedFields,
+        depth: usize,
+    ) -> Result<()> {
+        let join = |name: &str| {
+            if path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", path, name)
+            }
+        };
+        match fields {
+            IdlFields::Named(fields) => {
+                for (name, ty) in fields {
+                    self.decode_value(ty, data, &join(name), out, depth + 1)?;
+                }
+            }
+            IdlFields::Tuple(types) => {
+                for (i, ty) in types.iter().enumerate() {
+                    self.decode_value(ty, data, &join(&i.to_string()), out, depth + 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Borsh-decode a value of type `ty` into `(path, value)` pairs: struct
+    /// fields are joined with dots, sequences indexed with brackets and byte
+    /// arrays shown as hex
+    fn decode_value(
+        &self,
+        ty: &IdlType,
+        data: &mut &[u8],
+        path: &str,
+        out: &mut DecodedFields,
+        depth: usize,
+    ) -> Result<()> {
+        use borsh::BorshDeserialize;
+        if depth > MAX_DEPTH {
+            return Err(anyhow::anyhow!("IDL type nests too deeply at {}", path));
+        }
+        let value = match ty {
+            IdlType::Bool => bool::deserialize(data)?.to_string(),
+            IdlType::U8 => u8::deserialize(data)?.to_string(),
+            IdlType::I8 => i8::deserialize(data)?.to_string(),
+            IdlType::U16 => u16::deserialize(data)?.to_string(),
+            IdlType::I16 => i16::deserialize(data)?.to_string(),
+            IdlType::U32 => u32::deserialize(data)?.to_string(),
+            IdlType::I32 => i32::deserialize(data)?.to_string(),
+            IdlType::U64 => u64::deserialize(data)?.to_string(),
+            IdlType::I64 => i64::deserialize(data)?.to_string(),
+            IdlType::U128 => u128::deserialize(data)?.to_string(),
+            IdlType::I128 => i128::deserialize(data)?.to_string(),
+            IdlType::F32 => f32::deserialize(data)?.to_string(),
+            IdlType::F64 => f64::deserialize(data)?.to_string(),
+            IdlType::String => String::deserialize(data)?,
+            IdlType::Bytes => hex::encode(Vec::<u8>::deserialize(data)?),
+            IdlType::Pubkey => Pubkey::new_from_array(<[u8; 32]>::deserialize(data)?).to_string(),
+            IdlType::Option(inner) => {
+                if !bool::deserialize(data)? {
+                    out.push((path.to_string(), "None".to_string()));
+                    return Ok(());
+                }
+                return self.decode_value(inner, data, path, out, depth + 1);
+            }
+            IdlType::COption(inner) => {
+                if u32::deserialize(data)? == 0 {
+                    out.push((path.to_string(), "None".to_string()));
+                    return Ok(());
+                }
+                return self.decode_value(inner, data, path, out, depth + 1);
+            }
+            IdlType::Vec(inner) | IdlType::Array(inner, _) => {
+                let len = match ty {
+                    IdlType::Array(_, len) => *len,
+                    _ => u32::deserialize(data)? as usize,
+                };
+                if matches!(**inner, IdlType::U8) {
+                    let rest: &[u8] = data;
+                    let bytes = rest
+                        .get(..len)
+                        .ok_or_else(|| anyhow::anyhow!("Not enough data for {}", path))?;
+                    *data = &rest[len..];
+                    hex::encode(bytes)
+                } else {
+                    for i in 0..len {
+                        let item = format!("{}[{}]", path, i);
+                        self.decode_value(inner, data, &item, out, depth + 1)?;
+                    }
+                    return Ok(());
+                }
+            }
+            IdlType::Defined(name) => {
+                let def = self
+                    .types
+                    .get(name)
+                    .ok_or_else(|| anyhow::anyhow!("IDL type {} is not defined", name))?;
+                return match def {
+                    IdlTypeDef::Struct(fields) => {
+                        self.decode_fields(fields, data, path, out, depth)
+                    }
+                    IdlTypeDef::Alias(alias) => {
+                        self.decode_value(alias, data, path, out, depth + 1)
+                    }
+                    IdlTypeDef::Enum(variants) => {
+                        let index = u8::deserialize(data)? as usize;
+                        let (variant, fields) = variants.get(index).ok_or_else(|| {
+                            anyhow::anyhow!("Invalid variant {} of IDL enum {}", index, name)
+                        })?;
+                        out.push((path.to_string(), variant.clone()));
+                        match fields {
+                            Some(fields) => self.decode_fields(fields, data, path, out, depth),
+                            None => Ok(()),
+                        }
+                    }
+                };
+            }
+        };
+        out.push((path.to_string(), value));
+        Ok(())
+    }
+}
+
+/// Load an IDL file, given as `PATH` or `PROGRAM_ID=PATH`; the program ID is
+/// needed when the file does not carry the address
+pub fn load_file(spec: &str) -> Result<(Pubkey, Idl)> {
+    let (program_id, path) = match spec.split_once('=') {
+        Some((program_id, path)) => (Some(program_id), path),
+        None => (None, spec),
+    };
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read IDL {}", path))?;
+    let value: Value =
+        serde_json::from_str(&contents).with_context(|| format!("Failed to parse IDL {}", path))?;
+    let idl = Idl::parse(&value).with_context(|| format!("Failed to load IDL {}", path))?;
+
+    let address = program_id
+        .map(String::from)
+        .or_else(|| idl.address.clone())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "IDL {} has no program address; pass it as --idl <PROGRAM_ID>={}",
+                path,
+                path
+            )
+        })?;
+    let program_id = Pubkey::from_str(&address)
+        .with_context(|| format!("Failed to parse program ID {}", address))?;
+    Ok((program_id, idl))
+}
+
+/// Address of a program's on-chain IDL account, as created by `anchor idl init`
+pub fn idl_address(program_id: &Pubkey) -> Pubkey {
+    let (base, _) = Pubkey::find_program_address(&[], program_id);
+    Pubkey::create_with_seed(&base, "anchor:idl", program_id).unwrap()
+}
+
+/// Fetch and parse a program's on-chain IDL. The account holds an 8-byte
+/// discriminator, the authority, a u32 length and the zlib-compressed JSON.
+pub fn fetch(rpc: &Rpc, program_id: &Pubkey) -> Result<Idl> {
+    let (account, _) = rpc
+        .get_account(&idl_address(program_id))
+        .with_context(|| format!("No on-chain IDL for {}", program_id))?;
+    let data = &account.data;
+    let len = data
+        .get(40..44)
+        .map(|len| u32::from_le_bytes(len.try_into().unwrap()) as usize)
+        .ok_or_else(|| anyhow::anyhow!("IDL account of {} is too short", program_id))?;
+    let compressed = data
+        .get(44..44 + len)
+        .ok_or_else(|| anyhow::anyhow!("IDL account of {} is truncated", program_id))?;
+    let mut json = String::new();
+    ZlibDecoder::new(compressed)
+        .read_to_string(&mut json)
+        .with_context(|| format!("Failed to decompress the IDL of {}", program_id))?;
+    let value: Value = serde_json::from_str(&json)
+        .with_context(|| format!("Failed to parse the IDL of {}", program_id))?;
+    Idl::parse(&value)
+}
+
+fn registry() -> &'static Mutex<HashMap<Pubkey, Arc<Idl>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<Pubkey, Arc<Idl>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Use `idl` to decode the instructions and accounts of `program_id` for the
+/// rest of the process
+pub fn register(program_id: Pubkey, idl: Idl) {
+    registry().lock().unwrap().insert(program_id, Arc::new(idl));
+}
+
+pub fn get(program_id: &Pubkey) -> Option<Arc<Idl>> {
+    registry().lock().unwrap().get(program_id).cloned()
+}
+
+/// The registered IDL of `program_id`, or else its on-chain IDL, which is
+/// registered for later lookups. `None` when the program has no IDL.
+pub fn get_or_fetch(rpc: &Rpc, program_id: &Pubkey) -> Option<Arc<Idl>> {
+    if let Some(idl) = get(program_id) {
+        return Some(idl);
+    }
+    let idl = fetch(rpc, program_id).ok()?;
+    register(*program_id, idl);
+    get(program_id)
+}
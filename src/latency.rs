@@ -0,0 +1,216 @@
+use crate::BidEntry;
+use colored::*;
+use std::collections::BTreeMap;
+
+/// Target slot time, used to express a delay from `valid_from` (a Unix time)
+/// in slots
+const MS_PER_SLOT: i64 = 400;
+
+/// Widest histogram bar, in characters
+const BAR_WIDTH: usize = 40;
+
+/// Histogram buckets shown; slower reactions are counted in the last one
+const MAX_BUCKETS: i64 = 20;
+
+#[derive(Debug, Clone)]
+pub struct BidLatency {
+    pub signature: String,
+    pub bidder: String,
+    pub slot: u64,
+    pub failed: bool,
+    /// Milliseconds from `valid_from` to the bid's block time. Block times have
+    /// a resolution of one second.
+    pub since_start_ms: Option<i64>,
+    /// Slots and milliseconds since the previous bid of the auction
+    pub since_previous: Option<(u64, Option<i64>)>,
+}
+
+pub struct AuctionLatency {
+    pub auction: String,
+    pub valid_from: u64,
+    pub bids: Vec<BidLatency>,
+}
+
+impl AuctionLatency {
+    /// Delays of bids ordered by slot, as returned by `get_bid_history`
+    pub fn new(auction: &str, valid_from: u64, bids: &[BidEntry]) -> Self {
+        let mut latencies = Vec::new();
+        let mut previous: Option<&BidEntry> = None;
+        for bid in bids {
+            let since_previous = previous.map(|previous| {
+                let ms = bid
+                    .timestamp
+                    .zip(previous.timestamp)
+                    .map(|(time, previous)| (time - previous) * 1000);
+                (bid.slot.saturating_sub(previous.slot), ms)
+            });
+            latencies.push(BidLatency {
+                signature: bid.signature.clone(),
+                bidder: bid.bidder.clone(),
+                slot: bid.slot,
+                failed: bid.failed,
+                since_start_ms: bid.timestamp.map(|time| (time - valid_from as i64) * 1000),
+                since_previous,
+            });
+            previous = Some(bid);
+        }
+        AuctionLatency {
+            auction: auction.to_string(),
+            valid_from,
+            bids: latencies,
+        }
+    }
+
+    /// Reaction time of each bidder: the delay of its first bid in the auction
+    fn reactions(&self) -> BTreeMap<&str, i64> {
+        let mut reactions = BTreeMap::new();
+        for bid in &self.bids {
+            if let Some(ms) = bid.since_start_ms {
+                reactions.entry(bid.bidder.as_str()).or_insert(ms);
+            }
+        }
+        reactions
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+pub fn percentile(sorted: &[i64], p: usize) -> i64 {
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn format_ms(ms: i64) -> String {
+    format!(
+        "{}{}ms (~{} slots)",
+        if ms >= 0 { "+" } else { "" },
+        ms,
+        ms / MS_PER_SLOT
+    )
+}
+
+fn format_time(seconds: u64) -> String {
+    chrono::DateTime::from_timestamp(seconds as i64, 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| seconds.to_string())
+}
+
+/// ASCII histogram of sorted values in buckets of `bucket` wide, labelled
+/// with `unit`
+pub fn histogram(sorted: &[i64], bucket: i64, unit: &str) -> String {
+    let first = sorted[0].div_euclid(bucket);
+    let last = sorted[sorted.len() - 1]
+        .div_euclid(bucket)
+        .min(first + MAX_BUCKETS - 1);
+    let mut counts = vec![0usize; (last - first + 1) as usize];
+    for value in sorted {
+        let index = value.div_euclid(bucket).min(last);
+        counts[(index - first) as usize] += 1;
+    }
+    let max = counts.iter().copied().max().unwrap_or(1).max(1);
+
+    let mut result = String::new();
+    for (i, count) in counts.iter().enumerate() {
+        let start = (first + i as i64) * bucket;
+        let label = if first + i as i64 == last && sorted[sorted.len() - 1] >= start + bucket {
+            format!(">= {}{}", start, unit)
+        } else {
+            format!("{}..{}{}", start, start + bucket, unit)
+        };
+        result.push_str(&format!(
+            "\n    {:>16} {:<width$} {}",
+            label,
+            "#".repeat(count * BAR_WIDTH / max),
+            count,
+            width = BAR_WIDTH
+        ));
+    }
+    result
+}
+
+pub fn format_auction_latency(auction: &AuctionLatency) -> String {
+    let mut result = format!(
+        "{} {}\n  {}: {} ({})",
+        "Auction".cyan(),
+        auction.auction,
+        "Valid From".green(),
+        auction.valid_from,
+        format_time(auction.valid_from)
+    );
+    if auction.bids.is_empty() {
+        result.push_str(&format!("\n  {}: No bids found", "Bids".yellow()));
+        return result;
+    }
+
+    for (i, bid) in auction.bids.iter().enumerate() {
+        result.push_str(&format!(
+            "\n  {} {}: {} ({}: {}{})\n    {}: {}\n    {}: {}",
+            "Bid".cyan(),
+            i + 1,
+            bid.bidder,
+            "Slot".green(),
+            bid.slot,
+            if bid.failed {
+                format!(", {}", "Failed".red())
+            } else {
+                String::new()
+            },
+            "Signature".green(),
+            bid.signature,
+            "From Start".green(),
+            bid.since_start_ms
+                .map(format_ms)
+                .unwrap_or_else(|| "Unknown".to_string())
+        ));
+        if let Some((slots, ms)) = bid.since_previous {
+            result.push_str(&format!(
+                "\n    {}: +{} slots, {}",
+                "From Previous".green(),
+                slots,
+                ms.map(|ms| format!("+{}ms", ms))
+                    .unwrap_or_else(|| "Unknown".to_string())
+            ));
+        }
+    }
+    result
+}
+
+/// Median and p90 reaction time of each bidder across the auctions, with a
+/// histogram of its reaction times in `bucket_ms` buckets
+pub fn format_reaction_summary(auctions: &[AuctionLatency], bucket_ms: i64) -> String {
+    let mut by_bidder: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+    for auction in auctions {
+        for (bidder, ms) in auction.reactions() {
+            by_bidder.entry(bidder).or_default().push(ms);
+        }
+    }
+    if by_bidder.is_empty() {
+        return format!("{}: No bids with a block time", "Reaction Times".yellow());
+    }
+
+    let mut bidders: Vec<(&str, Vec<i64>)> = by_bidder.into_iter().collect();
+    for (_, reactions) in &mut bidders {
+        reactions.sort_unstable();
+    }
+    // Fastest bidders first
+    bidders.sort_by_key(|(_, reactions)| percentile(reactions, 50));
+
+    let mut result = format!(
+        "{}: first bid of each bidder after valid_from, over {} auctions",
+        "Reaction Times".green(),
+        auctions.len()
+    );
+    for (bidder, reactions) in &bidders {
+        result.push_str(&format!(
+            "\n\n  {}\n    {}: {}\n    {}: {}\n    {}: {}{}",
+            bidder.cyan(),
+            "Auctions".green(),
+            reactions.len(),
+            "Median".green(),
+            format_ms(percentile(reactions, 50)),
+            "P90".green(),
+            format_ms(percentile(reactions, 90)),
+            histogram(reactions, bucket_ms, "ms")
+        ));
+    }
+    result
+}
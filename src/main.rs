@@ -3,16 +3,40 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use clap::{Parser, Subcommand};
 use colored::*;
 use serde::{Deserialize, Serialize};
-use solana_client::rpc_client::RpcClient;
-use solana_client::rpc_config::RpcTransactionConfig;
-use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Signature;
+use solana_sdk::signature::{read_keypair_file, Signature, Signer};
+use solana_sdk::transaction::Transaction;
 use solana_transaction_status::{
-    EncodedTransaction, UiInstruction, UiMessage, UiParsedInstruction, UiTransactionEncoding,
+    EncodedTransaction, UiInnerInstructions, UiInstruction, UiMessage, UiParsedInstruction,
+    UiTransactionEncoding,
 };
+use std::io::Write;
 use std::str::FromStr;
 
+mod alert;
+mod bid;
+mod cursor;
+mod decoder;
+mod efficiency;
+mod errors;
+mod events;
+mod explorer;
+mod exporter;
+mod fees;
+mod idl;
+mod latency;
+mod my_bids;
+mod order;
+mod programs;
+mod reconcile;
+mod rpc;
+mod serve;
+mod snapshot;
+mod timeline;
+mod track;
+mod transport;
+mod vaa;
+
 #[derive(Parser)]
 #[command(name = "mayan-cli")]
 #[command(about = "A CLI utility for Mayan Finance operations")]
@@ -20,6 +44,40 @@ use std::str::FromStr;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Commitment level of RPC reads (defaults to finalized for accounts and
+    /// confirmed for transaction history)
+    #[arg(long, global = true, value_parser = ["processed", "confirmed", "finalized"])]
+    commitment: Option<String>,
+    /// Fail RPC reads served by a node that has not reached this slot
+    #[arg(long, global = true)]
+    min_context_slot: Option<u64>,
+    /// Seconds to wait for an RPC endpoint before failing over to the next one
+    #[arg(long, global = true, default_value_t = 30)]
+    rpc_timeout: u64,
+    /// Save every RPC and API request and response to this directory
+    #[arg(long, global = true, conflicts_with = "replay")]
+    record: Option<String>,
+    /// Serve RPC and API responses from a directory made by --record, without
+    /// touching the network
+    #[arg(long, global = true)]
+    replay: Option<String>,
+    /// Cluster the Swift programs are deployed on; sets the default RPC endpoint
+    /// and explorer API
+    #[arg(long, global = true, default_value = "mainnet", value_parser = ["mainnet", "devnet", "localnet"])]
+    cluster: String,
+    /// Swift program ID, for deployments other than mainnet's
+    #[arg(long, global = true)]
+    swift_program_id: Option<String>,
+    /// Swift auction program ID, for deployments other than mainnet's
+    #[arg(long, global = true)]
+    auction_program_id: Option<String>,
+    /// Mayan explorer API endpoint (defaults to the mainnet explorer on mainnet, none elsewhere) or env var MAYAN_EXPLORER_API_URL
+    #[arg(long, global = true, env = "MAYAN_EXPLORER_API_URL")]
+    explorer_api: Option<String>,
+    /// Anchor IDL file to decode a program's instructions and accounts with, as
+    /// PATH or PROGRAM_ID=PATH when the IDL has no address; can be repeated
+    #[arg(long, global = true)]
+    idl: Vec<String>,
 }
 
 #[derive(Subcommand)]
@@ -35,18 +93,25 @@ enum Commands {
     GetAuctionState {
         /// The order ID or auction state address to query
         input: String,
-        /// Solana RPC endpoint (optional, defaults to mainnet) or env var SOLANA_RPC_URL
-        #[arg(long, default_value = "https://api.mainnet-beta.solana.com", env = "SOLANA_RPC_URL")]
-        rpc_url: String,
+        /// Read the account from every RPC endpoint and report disagreements
+        #[arg(long)]
+        quorum: bool,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
     },
     /// Get bid information from auction state address or order ID [alias: gb]
     #[command(alias = "gb")]
     GetBids {
         /// The order ID or auction state address to query
         input: String,
-        /// Solana RPC endpoint (optional, defaults to mainnet) or env var SOLANA_RPC_URL
-        #[arg(long, default_value = "https://api.mainnet-beta.solana.com", env = "SOLANA_RPC_URL")]
-        rpc_url: String,
+        /// Exit with code 5 when the history does not reconcile with the
+        /// auction result, or the auction state could not be read
+        #[arg(long)]
+        strict: bool,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
     },
     /// Decode a base58 encoded string [alias: b58d]
     #[command(alias = "b58d")]
@@ -87,6 +152,284 @@ enum Commands {
         #[arg(long, default_value = "hex")]
         output_format: String,
     },
+    /// Decode a Wormhole VAA from base64, hex or a file, including Mayan payloads [alias: dv]
+    #[command(alias = "dv")]
+    DecodeVaa {
+        /// The VAA as a base64 or hex string, or a path to a file containing it
+        input: String,
+        /// Input format: auto, base64, or hex (files with binary content are read as raw bytes)
+        #[arg(long, default_value = "auto")]
+        format: String,
+    },
+    /// Fetch and decode the Wormhole VAA for an auction's settlement message [alias: gav]
+    #[command(alias = "gav")]
+    GetAuctionVaa {
+        /// The order ID or auction state address to query
+        input: String,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+        /// Wormhole guardian or Wormholescan API endpoint or env var WORMHOLE_API_URL
+        #[arg(
+            long,
+            default_value = "https://api.wormholescan.io",
+            env = "WORMHOLE_API_URL"
+        )]
+        wormhole_api: String,
+    },
+    /// Recompute the Swift order hash from order parameters and compare it [alias: coh]
+    #[command(alias = "coh")]
+    ComputeOrderHash {
+        #[command(flatten)]
+        params: Box<order::OrderParams>,
+        /// JSON file with order parameters (camelCase keys); flags take precedence
+        #[arg(long)]
+        json: Option<String>,
+        /// Order ID whose explorer record provides the parameters; flags and --json take precedence
+        #[arg(long)]
+        order: Option<String>,
+        /// Expected order ID (SWIFT_0x...) or hash to compare against (defaults to --order)
+        #[arg(long)]
+        expect: Option<String>,
+        /// Order ID or auction state address whose on-chain hash to compare against
+        #[arg(long)]
+        auction: Option<String>,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Simulate a bid on a live auction without signing it [alias: sb]
+    #[command(alias = "sb")]
+    SimulateBid {
+        /// The order ID or auction state address to bid on
+        input: String,
+        /// Bid amount (amount promised, in normalized units)
+        #[arg(long)]
+        amount: u64,
+        /// Public key of the bidder (driver) paying for the transaction
+        #[arg(long)]
+        bidder: String,
+        /// JSON file with the order parameters (defaults to the explorer record or an earlier bid)
+        #[arg(long)]
+        json: Option<String>,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Decode every instruction of a transaction, including inner instructions [alias: dtx]
+    #[command(alias = "dtx")]
+    DecodeTx {
+        /// The transaction signature
+        signature: String,
+        /// Fetch the on-chain IDL of invoked programs that have no --idl
+        #[arg(long)]
+        fetch_idl: bool,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Decode an account with the IDL of its owner program (from --idl or
+    /// on-chain), falling back to the built-in AuctionState layout [alias: dacc]
+    #[command(alias = "dacc")]
+    DecodeAccount {
+        /// The account address
+        address: String,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Poll an order until it completes, is refunded or fails; the exit code
+    /// is 0 when completed, 2 when refunded, 3 when failed and 4 on timeout [alias: tr]
+    #[command(alias = "tr")]
+    Track {
+        /// The order ID to track
+        order_id: String,
+        /// Seconds between polls
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+        /// Seconds to wait for a final state before giving up (0 waits forever)
+        #[arg(long, default_value_t = 600)]
+        timeout: u64,
+        /// Also poll the on-chain AuctionState and report winner changes
+        #[arg(long)]
+        onchain: bool,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// List decoded Anchor events from an account's recent transactions [alias: ev]
+    #[command(alias = "ev")]
+    Events {
+        /// The account address whose transactions to scan
+        address: String,
+        /// Maximum number of recent transactions to scan
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Save the decoded AuctionState with its slot to a JSON snapshot [alias: snap]
+    #[command(alias = "snap")]
+    Snapshot {
+        /// The order ID or auction state address to snapshot
+        input: String,
+        /// File to write the snapshot to (printed to stdout if omitted)
+        #[arg(long)]
+        out: Option<String>,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Show which AuctionState fields changed between two snapshots
+    Diff {
+        /// Earlier snapshot file, or an order ID or auction state address to read live
+        before: String,
+        /// Later snapshot file, or an order ID or auction state address to read live
+        after: String,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Chronological timeline of every transaction touching an order [alias: tl]
+    #[command(alias = "tl")]
+    Timeline {
+        /// The order ID or auction state address to query
+        input: String,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Sign and send a bid on a live auction
+    Bid {
+        /// The order ID or auction state address to bid on
+        input: String,
+        /// Bid amount (amount promised, in normalized units)
+        #[arg(long)]
+        amount: u64,
+        /// Path to the bidder's Solana keypair file or env var SOLANA_KEYPAIR
+        #[arg(long, env = "SOLANA_KEYPAIR")]
+        keypair: String,
+        /// Compute unit limit for the transaction
+        #[arg(long)]
+        compute_unit_limit: Option<u32>,
+        /// Priority fee in micro-lamports per compute unit
+        #[arg(long)]
+        priority_fee: Option<u64>,
+        /// Decimals of the normalized amounts, used to show UI units
+        #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u32).range(..=18))]
+        decimals: u32,
+        /// JSON file with the order parameters (defaults to the explorer record or an earlier bid)
+        #[arg(long)]
+        json: Option<String>,
+        /// Simulate the bid instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip the interactive confirmation
+        #[arg(long)]
+        yes: bool,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Bid delays from auction start and between bids, with per-bidder reaction times [alias: lat]
+    #[command(alias = "lat")]
+    Latency {
+        /// Order IDs or auction state addresses to analyze
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Width of the reaction time histogram buckets, in milliseconds
+        #[arg(long, default_value_t = 1000)]
+        bucket_ms: u64,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Compare auction results with amount_out_min and a reference quote across orders [alias: eff]
+    #[command(alias = "eff")]
+    Efficiency {
+        /// Order IDs to analyze
+        #[arg(required = true)]
+        order_ids: Vec<String>,
+        /// Quote API endpoint for the reference quotes or env var MAYAN_QUOTE_API_URL
+        #[arg(long, env = "MAYAN_QUOTE_API_URL")]
+        quote_api: String,
+        /// Width of the improvement histogram buckets, in bps
+        #[arg(long, default_value_t = 10)]
+        bucket_bps: u64,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Scan a bidder's transactions for bids and show how each auction went [alias: mb]
+    #[command(alias = "mb")]
+    MyBids {
+        /// Public key of the bidder (driver)
+        bidder: String,
+        /// Maximum number of recent transactions to scan
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Watch the auction program and expose Prometheus metrics over HTTP
+    Exporter {
+        /// Address to serve /metrics on
+        #[arg(long, default_value = "127.0.0.1:9464")]
+        listen: String,
+        /// Accounts to watch besides the auction program (auction states, solver wallets), comma-separated
+        #[arg(long, value_delimiter = ',')]
+        watch: Vec<String>,
+        /// Seconds between polls
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+        /// Transactions read per account on the first poll
+        #[arg(long, default_value_t = 100)]
+        recent: usize,
+        /// Auctions kept for the per-auction metrics, most recently active first
+        #[arg(long, default_value_t = 1000)]
+        max_auctions: usize,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Post webhook alerts when our bidders are outbid, win or have a bid fail
+    Alert {
+        /// Our bidder (driver) public keys, comma-separated
+        #[arg(long, required = true, value_delimiter = ',')]
+        bidders: Vec<String>,
+        /// URL the JSON alerts are POSTed to or env var MAYAN_ALERT_WEBHOOK
+        #[arg(long, env = "MAYAN_ALERT_WEBHOOK")]
+        webhook: String,
+        /// Seconds between polls
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+        /// Transactions read per bidder on the first poll (0 only follows bids made from now on)
+        #[arg(long, default_value_t = 0)]
+        recent: usize,
+        /// Delivery attempts after the first one fails
+        #[arg(long, default_value_t = 3)]
+        retries: u32,
+        /// File that undeliverable alerts are appended to, one JSON object per line
+        #[arg(long, default_value = "alerts-dead-letter.jsonl")]
+        dead_letter: String,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
+    /// Serve auction and bid data as JSON over HTTP
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// Seconds a response is served from cache before it is fetched again
+        #[arg(long, default_value_t = 5)]
+        cache_ttl: u64,
+        /// Solana RPC endpoints, comma-separated for failover (optional, defaults to the --cluster endpoint) or env var SOLANA_RPC_URL
+        #[arg(long, env = "SOLANA_RPC_URL", value_delimiter = ',')]
+        rpc_url: Vec<String>,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -119,63 +462,162 @@ pub struct BidEntry {
     pub slot: u64,
     pub timestamp: Option<i64>,
     pub failed: bool,
+    /// Decoded `meta.err` of a failed bid
+    pub error: Option<errors::DecodedError>,
+    /// Log lines of the failed program invocation
+    pub error_logs: Vec<String>,
+    pub fees: fees::BidFees,
+    /// Set when other bids of the auction landed in the same slot
+    pub same_slot: Option<SameSlot>,
+    pub events: Vec<events::DecodedEvent>,
 }
 
-async fn get_auction_state_addr(order_id: &str) -> Result<String> {
-    let url = format!(
-        "https://explorer-api.mayan.finance/v3/swap/order-id/{}",
-        order_id
-    );
-
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .context("Failed to send request to Mayan API")?;
-
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "API request failed with status: {}",
-            response.status()
-        ));
-    }
+/// Where a bid landed among the auction's other bids in the same slot
+#[derive(Debug, Clone)]
+pub struct SameSlot {
+    /// 1-based position among the bids of the slot
+    pub rank: usize,
+    pub bids: usize,
+    /// Index of the transaction in the block and the block's transaction
+    /// count; `None` when the block could not be read
+    pub block_position: Option<(usize, usize)>,
+}
 
-    let order_data: MayanOrderResponse = response
-        .json()
-        .await
-        .context("Failed to parse JSON response")?;
+async fn get_auction_state_addr(
+    order_id: &str,
+    explorer: &explorer::ExplorerApi,
+) -> Result<String> {
+    let record = explorer.order_record(order_id).await?;
+    let order_data: MayanOrderResponse =
+        serde_json::from_value(record).context("Failed to parse JSON response")?;
 
     Ok(order_data.auction_state_addr)
 }
 
-async fn get_and_parse_auction_state(input: &str, rpc_url: &str) -> Result<AuctionState> {
+async fn resolve_auction_state_addr(
+    input: &str,
+    explorer: &explorer::ExplorerApi,
+) -> Result<String> {
     // Determine if input is an order ID or auction state address
     // Solana addresses are base58 encoded and typically 32-44 characters
     // Try to parse as Pubkey first to see if it's a valid address
-    let auction_state_addr = match Pubkey::from_str(input) {
+    match Pubkey::from_str(input) {
         Ok(_) => {
             // Input is already a valid Pubkey (auction state address)
-            input.to_string()
+            Ok(input.to_string())
         }
         Err(_) => {
             // Input is likely an order ID, fetch auction state address from API
-            get_auction_state_addr(input).await?
+            get_auction_state_addr(input, explorer).await
         }
-    };
+    }
+}
 
-    // Connect to Solana RPC
-    let client = RpcClient::new(rpc_url.to_string());
+/// Returns the auction state with the context slot it was read at
+async fn get_and_parse_auction_state(
+    input: &str,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<(AuctionState, u64)> {
+    let auction_state_addr = resolve_auction_state_addr(input, explorer).await?;
 
     // Parse the auction state address as a Pubkey
     let pubkey = Pubkey::from_str(&auction_state_addr)
         .context("Failed to parse auction state address as Pubkey")?;
 
     // Fetch the account data
-    let account_data = client
-        .get_account_data(&pubkey)
-        .context("Failed to fetch account data from Solana")?;
+    let (account, context_slot) = rpc.get_account(&pubkey)?;
+
+    Ok((parse_auction_state_data(&account.data)?, context_slot))
+}
 
+/// Read the auction state from every RPC endpoint and compare the data and
+/// slots they serve. The state shown is the one from the most recent slot.
+/// Returns the report and whether every responding endpoint served the same data.
+async fn get_auction_state_quorum(
+    input: &str,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<(String, bool)> {
+    let auction_state_addr = resolve_auction_state_addr(input, explorer).await?;
+    let pubkey = Pubkey::from_str(&auction_state_addr)
+        .context("Failed to parse auction state address as Pubkey")?;
+
+    let reads = rpc.get_account_from_all(&pubkey);
+    let mut result = format!("Quorum Read ({} endpoints):", rpc.endpoint_count());
+    let mut responses = Vec::new();
+    for read in &reads {
+        match &read.result {
+            Ok((account, slot)) => {
+                let digest = solana_sdk::hash::hash(&account.data);
+                result.push_str(&format!(
+                    "\n  {}: {}: {}, {}: {}",
+                    read.url,
+                    "Slot".green(),
+                    slot,
+                    "Data Hash".green(),
+                    &digest.to_string()[..8]
+                ));
+                responses.push((read.url.as_str(), account, *slot));
+            }
+            Err(e) => {
+                result.push_str(&format!("\n  {}: {}: {}", read.url, "Error".red(), e));
+            }
+        }
+    }
+
+    let (_, latest_account, latest_slot) = *responses
+        .iter()
+        .max_by_key(|(_, _, slot)| *slot)
+        .ok_or_else(|| anyhow::anyhow!("No RPC endpoint returned the auction state"))?;
+
+    let mut versions: Vec<&[u8]> = responses
+        .iter()
+        .map(|(_, account, _)| account.data.as_slice())
+        .collect();
+    versions.sort();
+    versions.dedup();
+    let data_agrees = versions.len() == 1;
+    result.push_str(&format!(
+        "\n  {}: {} ({} of {} endpoints responded)",
+        "Data".green(),
+        if data_agrees {
+            "Agree".green()
+        } else {
+            format!("Disagree, {} different versions", versions.len()).red()
+        },
+        responses.len(),
+        reads.len()
+    ));
+
+    let lagging: Vec<String> = responses
+        .iter()
+        .filter(|(_, _, slot)| *slot < latest_slot)
+        .map(|(url, _, slot)| format!("{} ({} slots behind)", url, latest_slot - slot))
+        .collect();
+    if lagging.is_empty() {
+        result.push_str(&format!(
+            "\n  {}: all at slot {}",
+            "Slots".green(),
+            latest_slot
+        ));
+    } else {
+        result.push_str(&format!(
+            "\n  {}: {}",
+            "Lagging".yellow(),
+            lagging.join(", ")
+        ));
+    }
+
+    let auction_state = parse_auction_state_data(&latest_account.data)?;
+    result.push_str(&format!(
+        "\n\n{}",
+        format_auction_state(&auction_state, latest_slot)
+    ));
+    Ok((result, data_agrees))
+}
+
+fn parse_auction_state_data(account_data: &[u8]) -> Result<AuctionState> {
     // Try to deserialize the account data using Borsh
     // Note: Some accounts may have a discriminator prefix, let's try with and without
     let auction_state = if account_data.len() >= 8 {
@@ -184,40 +626,90 @@ async fn get_and_parse_auction_state(input: &str, rpc_url: &str) -> Result<Aucti
             Ok(state) => state,
             Err(_) => {
                 // Fall back to deserializing from the beginning
-                AuctionState::try_from_slice(&account_data)
+                AuctionState::try_from_slice(account_data)
                     .context("Failed to deserialize auction state data (tried both with and without discriminator)")?
             }
         }
     } else {
-        AuctionState::try_from_slice(&account_data)
+        AuctionState::try_from_slice(account_data)
             .context("Failed to deserialize auction state data")?
     };
 
     Ok(auction_state)
 }
 
-async fn get_bid_history(auction_state_addr: &str, rpc_url: &str) -> Result<Vec<BidEntry>> {
-    let client = RpcClient::new(rpc_url.to_string());
+/// Read the AuctionState now, together with the slot it was read at
+async fn take_auction_snapshot(
+    input: &str,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<snapshot::AuctionSnapshot> {
+    let auction_state_addr = resolve_auction_state_addr(input, explorer).await?;
+    let (auction_state, context_slot) =
+        get_and_parse_auction_state(&auction_state_addr, explorer, rpc).await?;
+
+    Ok(snapshot::AuctionSnapshot::new(
+        &auction_state_addr,
+        context_slot,
+        &auction_state,
+    ))
+}
+
+/// A snapshot file when `source` is an existing file, otherwise a live read
+async fn load_or_take_snapshot(
+    source: &str,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<snapshot::AuctionSnapshot> {
+    let path = std::path::Path::new(source);
+    if path.is_file() {
+        snapshot::AuctionSnapshot::load(source)
+    } else if source.ends_with(".json") || source.contains(std::path::MAIN_SEPARATOR) {
+        Err(anyhow::anyhow!("Snapshot file {} not found", source))
+    } else {
+        take_auction_snapshot(source, explorer, rpc).await
+    }
+}
+
+/// Transactions of an auction state read for its bid history, newest first
+pub const BID_HISTORY_LIMIT: usize = 100;
+
+/// Bids of an auction with how much of its history they were read from
+pub struct BidHistory {
+    pub bids: Vec<BidEntry>,
+    /// Slot the node was at when the signatures were listed
+    pub context_slot: u64,
+    /// Signatures listed for the auction state; only the newest
+    /// `BID_HISTORY_LIMIT` are read
+    pub signatures: usize,
+    /// Winner and amount promised named by the `AuctionClosed` event, when the
+    /// transaction that closed the auction was read
+    pub closed: Option<(String, u64)>,
+}
+
+/// Returns the bids with the slot the node was at when the signatures were listed
+async fn get_bid_history(auction_state_addr: &str, rpc: &rpc::Rpc) -> Result<(Vec<BidEntry>, u64)> {
+    let history = read_bid_history(auction_state_addr, rpc).await?;
+    Ok((history.bids, history.context_slot))
+}
+
+async fn read_bid_history(auction_state_addr: &str, rpc: &rpc::Rpc) -> Result<BidHistory> {
     let pubkey = Pubkey::from_str(auction_state_addr)
         .context("Failed to parse auction state address as Pubkey")?;
 
-    let signatures = client
+    let context_slot = rpc.get_slot()?;
+    let signatures = rpc
         .get_signatures_for_address(&pubkey)
         .context("Failed to get signatures for auction state address")?;
 
+    let auction_program = programs::auction_program_id().to_string();
     let mut bids = Vec::new();
+    let mut closed = None;
 
-    // Limit to 100 transactions for performance
-    for sig_info in signatures.iter().take(100) {
+    // Limit to BID_HISTORY_LIMIT transactions for performance
+    for sig_info in signatures.iter().take(BID_HISTORY_LIMIT) {
         let signature = Signature::from_str(&sig_info.signature)?;
-        let transaction = client.get_transaction_with_config(
-            &signature,
-            RpcTransactionConfig {
-                encoding: Some(UiTransactionEncoding::JsonParsed),
-                max_supported_transaction_version: Some(0),
-                commitment: Some(CommitmentConfig::confirmed()),
-            },
-        )?;
+        let transaction = rpc.get_transaction(&signature, UiTransactionEncoding::JsonParsed)?;
 
         let meta = transaction
             .transaction
@@ -225,14 +717,29 @@ async fn get_bid_history(auction_state_addr: &str, rpc_url: &str) -> Result<Vec<
             .as_ref()
             .ok_or(anyhow::anyhow!("Failed to get transaction meta"))?;
 
-        let valid = meta
-            .log_messages
-            .as_ref()
-            .map(|logs| {
-                logs.iter()
-                    .any(|log| log.contains("Program log: Instruction: Bid"))
-            })
-            .unwrap_or(false);
+        let logs: Vec<String> = Option::from(meta.log_messages.clone()).unwrap_or_default();
+        if meta.err.is_none() && closed.is_none() {
+            closed = events::decode_events_from_logs(&logs)
+                .iter()
+                .find(|event| {
+                    event.program_id == auction_program
+                        && event.name.as_deref() == Some("AuctionClosed")
+                })
+                .and_then(|event| {
+                    let field = |name: &str| {
+                        event
+                            .fields
+                            .iter()
+                            .find(|(field, _)| field == name)
+                            .map(|(_, value)| value.clone())
+                    };
+                    Some((field("winner")?, field("amount_promised")?.parse().ok()?))
+                });
+        }
+
+        let valid = logs
+            .iter()
+            .any(|log| log.contains("Program log: Instruction: Bid"));
         if !valid {
             continue;
         }
@@ -250,17 +757,63 @@ async fn get_bid_history(auction_state_addr: &str, rpc_url: &str) -> Result<Vec<
             _ => continue,
         };
 
-        let instruction = message.instructions[2].clone();
-        let parsed = match instruction {
-            UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(parsed)) => parsed,
-            _ => {
-                println!("Not a parsed instruction");
+        // The bid is the auction program's instruction; the others are compute
+        // budget and similar
+        let parsed = match message
+            .instructions
+            .iter()
+            .find_map(|instruction| match instruction {
+                UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(parsed))
+                    if parsed.program_id == auction_program =>
+                {
+                    Some(parsed.clone())
+                }
+                _ => None,
+            }) {
+            Some(parsed) => parsed,
+            None => {
+                eprintln!(
+                    "Warning: no auction program instruction in {}",
+                    sig_info.signature
+                );
                 continue;
             }
         };
+        // A bid is the discriminator, the order and the amount; skip any other
+        // auction program instruction
+        let data = match bs58::decode(&parsed.data).into_vec() {
+            Ok(data) if data.len() >= 16 && data[..8] == bid::instruction_discriminator("bid") => {
+                data
+            }
+            _ => continue,
+        };
+
+        let mut bid_fees = fees::BidFees {
+            units_consumed: Option::from(meta.compute_units_consumed.clone()),
+            fee: meta.fee,
+            signatures: ui_transaction.signatures.len(),
+            ..fees::BidFees::default()
+        };
+        let compute_budget = solana_sdk::compute_budget::id().to_string();
+        for instruction in &message.instructions {
+            if let UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(decoded)) =
+                instruction
+            {
+                if decoded.program_id == compute_budget {
+                    if let Ok(data) = bs58::decode(&decoded.data).into_vec() {
+                        bid_fees.apply_compute_budget(&data);
+                    }
+                }
+            }
+            bid_fees.apply_transfer(instruction);
+        }
+        // Tips are also paid through CPI, from a tip program or a router
+        let inner: Option<Vec<UiInnerInstructions>> = meta.inner_instructions.clone().into();
+        for instruction in inner.iter().flatten().flat_map(|inner| &inner.instructions) {
+            bid_fees.apply_transfer(instruction);
+        }
 
         let bidder = message.account_keys[0].pubkey.clone();
-        let data = bs58::decode(parsed.data).into_vec().unwrap();
         let bid_amount = u64::from_le_bytes(data[data.len() - 8..].try_into().unwrap());
 
         bids.push(BidEntry {
@@ -270,16 +823,739 @@ async fn get_bid_history(auction_state_addr: &str, rpc_url: &str) -> Result<Vec<
             slot: sig_info.slot,
             timestamp: sig_info.block_time,
             failed,
+            error: meta
+                .err
+                .as_ref()
+                .map(|err| errors::decode_transaction_error(err, &logs)),
+            error_logs: if failed {
+                errors::failure_logs(&logs)
+            } else {
+                Vec::new()
+            },
+            fees: bid_fees,
+            same_slot: None,
+            events: events::decode_events_from_logs(&logs),
         });
     }
 
     // Sort bids by slot (chronological order)
-    bids.sort_by(|a, b| a.slot.cmp(&b.slot));
+    bids.sort_by_key(|bid| bid.slot);
+    order_same_slot_bids(&mut bids, rpc);
+
+    Ok(BidHistory {
+        bids,
+        context_slot,
+        signatures: signatures.len(),
+        closed,
+    })
+}
+
+/// Reconcile the bid history with the auction state, or with how the auction
+/// was closed once its account is gone
+fn reconcile_bid_history(
+    auction_state_addr: &str,
+    history: &BidHistory,
+    rpc: &rpc::Rpc,
+) -> Result<reconcile::Reconciliation> {
+    let pubkey = Pubkey::from_str(auction_state_addr)
+        .context("Failed to parse auction state address as Pubkey")?;
+    match rpc.get_optional_account(&pubkey)? {
+        (Some(account), _) => Ok(reconcile::reconcile(
+            history,
+            &parse_auction_state_data(&account.data)?,
+        )),
+        (None, _) => Ok(reconcile::reconcile_closed(history)),
+    }
+}
+
+/// Order bids that landed in the same slot by their position in the block and
+/// mark them. A block that cannot be read keeps the RPC's order.
+fn order_same_slot_bids(bids: &mut [BidEntry], rpc: &rpc::Rpc) {
+    let mut start = 0;
+    while start < bids.len() {
+        let slot = bids[start].slot;
+        let end = start
+            + bids[start..]
+                .iter()
+                .take_while(|bid| bid.slot == slot)
+                .count();
+        let group = &mut bids[start..end];
+        if group.len() > 1 {
+            let block = match rpc.get_block_signatures(slot) {
+                Ok(signatures) => Some(signatures),
+                Err(e) => {
+                    eprintln!("Warning: {:#}", e);
+                    None
+                }
+            };
+            let position = |signature: &str| {
+                block.as_ref().and_then(|block| {
+                    block
+                        .iter()
+                        .position(|s| s == signature)
+                        .map(|index| (index, block.len()))
+                })
+            };
+            group.sort_by_key(|bid| position(&bid.signature).map_or(usize::MAX, |(i, _)| i));
+            let count = group.len();
+            for (rank, bid) in group.iter_mut().enumerate() {
+                bid.same_slot = Some(SameSlot {
+                    rank: rank + 1,
+                    bids: count,
+                    block_position: position(&bid.signature),
+                });
+            }
+        }
+        start = end;
+    }
+}
+
+async fn get_auction_vaa(
+    input: &str,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+    http: &dyn transport::HttpApi,
+    wormhole_api: &str,
+) -> Result<String> {
+    let (auction_state, context_slot) = get_and_parse_auction_state(input, explorer, rpc).await?;
+    let emitter = programs::derive_emitter(&programs::auction_program_id());
+
+    let mut result = format!(
+        "{}: {}\n{}: {}\n{}: {}\n{}: {}\n",
+        "Context Slot".green(),
+        context_slot,
+        "Emitter Chain".green(),
+        programs::SOLANA_WORMHOLE_CHAIN_ID,
+        "Emitter".green(),
+        emitter,
+        "Sequence".green(),
+        auction_state.seq_msg
+    );
+
+    // The auction program sets seq_msg once it has posted the result message
+    if auction_state.seq_msg == 0 {
+        result.push_str(&format!(
+            "{}: Auction result has not been posted to Wormhole yet",
+            "VAA".yellow()
+        ));
+        return Ok(result);
+    }
+
+    let vaa_bytes = vaa::fetch_signed_vaa(
+        http,
+        wormhole_api,
+        programs::SOLANA_WORMHOLE_CHAIN_ID,
+        &emitter.to_bytes(),
+        auction_state.seq_msg,
+    )
+    .await?;
+
+    match vaa_bytes {
+        Some(bytes) => {
+            let vaa = vaa::parse_vaa(&bytes)?;
+            result.push_str(&format!("\n{}", vaa::format_vaa(&vaa)));
+        }
+        None => {
+            result.push_str(&format!(
+                "{}: Message is not signed by the guardians yet (not found at {})",
+                "VAA".yellow(),
+                wormhole_api
+            ));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Compute the order hash and compare it with the expected hash and/or the
+/// on-chain `AuctionState`. Returns the report and whether every comparison matched.
+async fn compute_order_hash_report(
+    params: Box<order::OrderParams>,
+    json: Option<&str>,
+    order_id: Option<&str>,
+    expect: Option<&str>,
+    auction: Option<&str>,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<(String, bool)> {
+    let mut params = *params;
+    if let Some(path) = json {
+        params = params.or(order::OrderParams::from_json_file(path)?);
+    }
+    if let Some(order_id) = order_id {
+        let record = explorer.order_record(order_id).await?;
+        params = params.or(order::OrderParams::from_explorer_record(&record)?);
+    }
+
+    let order_info = params.to_order_info()?;
+    let hash = order::compute_order_hash(&order_info);
+    let mut result = order::format_order_encoding(&order_info, &hash);
+    let mut all_match = true;
+
+    let mut compare = |label: &str, expected: [u8; 32]| {
+        let matched = expected == hash;
+        all_match &= matched;
+        result.push_str(&format!(
+            "\n{}: {} {}",
+            label.green(),
+            hex::encode(expected),
+            if matched {
+                "Match".green()
+            } else {
+                "Mismatch".red()
+            }
+        ));
+    };
+
+    // A Swift order ID embeds its hash, so --order doubles as the expectation
+    let expect = expect.or(order_id.filter(|id| id.starts_with("SWIFT_")));
+    if let Some(expect) = expect {
+        compare("Expected", order::parse_order_hash(expect)?);
+    }
+    if let Some(auction) = auction {
+        let (auction_state, context_slot) =
+            get_and_parse_auction_state(auction, explorer, rpc).await?;
+        compare(
+            &format!("Auction State Hash (slot {})", context_slot),
+            auction_state.hash,
+        );
+    }
+
+    Ok((result, all_match))
+}
+
+/// Order parameters for a bid: JSON file, then the explorer record, then an earlier bid.
+/// Returns the order and a description of where it came from.
+async fn resolve_order_info(
+    input: &str,
+    json: Option<&str>,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+    auction_pubkey: &Pubkey,
+) -> Result<(order::OrderInfo, &'static str)> {
+    if let Some(path) = json {
+        Ok((
+            order::OrderParams::from_json_file(path)?.to_order_info()?,
+            "JSON file",
+        ))
+    } else if Pubkey::from_str(input).is_err() {
+        let record = explorer.order_record(input).await?;
+        Ok((
+            order::OrderParams::from_explorer_record(&record)?.to_order_info()?,
+            "explorer record",
+        ))
+    } else {
+        let order_info =
+            bid::find_order_info_in_history(rpc, auction_pubkey)?.ok_or_else(|| {
+                anyhow::anyhow!("No earlier bid found to recover the order parameters, pass --json")
+            })?;
+        Ok((order_info, "earlier bid"))
+    }
+}
+
+async fn simulate_bid_report(
+    input: &str,
+    amount: u64,
+    bidder: &str,
+    json: Option<&str>,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<String> {
+    let auction_state_addr = resolve_auction_state_addr(input, explorer).await?;
+    let auction_pubkey = Pubkey::from_str(&auction_state_addr)
+        .context("Failed to parse auction state address as Pubkey")?;
+    let bidder = Pubkey::from_str(bidder).context("Failed to parse bidder as Pubkey")?;
+    let (auction_state, context_slot) =
+        get_and_parse_auction_state(&auction_state_addr, explorer, rpc).await?;
+    let (order_info, source) =
+        resolve_order_info(input, json, explorer, rpc, &auction_pubkey).await?;
+
+    let mut result = format!(
+        "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n",
+        "Auction State Address".green(),
+        auction_state_addr,
+        "Context Slot".green(),
+        context_slot,
+        "Bidder".green(),
+        bidder,
+        "Amount".green(),
+        amount,
+        "Order Parameters".green(),
+        source
+    );
+
+    if order::compute_order_hash(&order_info) != auction_state.hash {
+        result.push_str(&format!(
+            "{}: order hash does not match AuctionState.hash, the bid will be rejected\n",
+            "Warning".yellow()
+        ));
+    }
+
+    let instruction = bid::build_bid_instruction(&auction_pubkey, &bidder, &order_info, amount)?;
+    let (simulation, simulation_slot) = bid::simulate_instructions(rpc, &[instruction], &bidder)?;
+    result.push_str(&format!(
+        "\n{}",
+        bid::format_simulation(&simulation, simulation_slot)
+    ));
+
+    Ok(result)
+}
+
+/// Decoded events of an account's most recent transactions, oldest first, with
+/// the slot the node was at when the signatures were listed
+fn get_account_events(
+    address: &str,
+    limit: usize,
+    rpc: &rpc::Rpc,
+) -> Result<(Vec<decoder::DecodedTransaction>, u64)> {
+    let pubkey = Pubkey::from_str(address).context("Failed to parse address as Pubkey")?;
+
+    let context_slot = rpc.get_slot()?;
+    let signatures = rpc.get_signatures_for_address(&pubkey)?;
+
+    let mut transactions = Vec::new();
+    for sig_info in signatures.iter().take(limit) {
+        let signature = Signature::from_str(&sig_info.signature)?;
+        let transaction = decoder::fetch_transaction(rpc, &signature)?;
+        let decoded = decoder::decode_transaction(&transaction)?;
+        if !decoded.events.is_empty() {
+            transactions.push(decoded);
+        }
+    }
+
+    transactions.sort_by_key(|transaction| transaction.slot);
+    Ok((transactions, context_slot))
+}
+
+fn format_account_events(
+    transactions: &[decoder::DecodedTransaction],
+    context_slot: u64,
+) -> String {
+    let count: usize = transactions.iter().map(|tx| tx.events.len()).sum();
+    let header = format!("{}: {}\n", "Context Slot".green(), context_slot);
+    if count == 0 {
+        return format!("{}{}: No events found", header, "Events".yellow());
+    }
+
+    let mut result = format!("{}{}: {} events found\n", header, "Events".green(), count);
+    for transaction in transactions {
+        result.push_str(&format!(
+            "\n{} {} ({}: {}, {}: {}, {})",
+            "Transaction".cyan(),
+            transaction.signature,
+            "Slot".green(),
+            transaction.slot,
+            "Timestamp".green(),
+            transaction.block_time.unwrap_or(0),
+            if transaction.err.is_some() {
+                "Failed".red()
+            } else {
+                "Success".green()
+            }
+        ));
+        result.push_str(&events::format_events(&transaction.events, "  "));
+    }
+    result
+}
+
+/// Latency of every bid of each auction, followed by the reaction times of
+/// each bidder across all of them
+async fn get_bid_latency(
+    inputs: &[String],
+    bucket_ms: u64,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<String> {
+    let mut auctions = Vec::new();
+    for input in inputs {
+        let auction_state_addr = resolve_auction_state_addr(input, explorer).await?;
+        let (auction_state, _) =
+            get_and_parse_auction_state(&auction_state_addr, explorer, rpc).await?;
+        let (bids, _) = get_bid_history(&auction_state_addr, rpc).await?;
+        auctions.push(latency::AuctionLatency::new(
+            &auction_state_addr,
+            auction_state.valid_from,
+            &bids,
+        ));
+    }
+
+    let mut result = String::new();
+    for auction in &auctions {
+        result.push_str(&format!("{}\n\n", latency::format_auction_latency(auction)));
+    }
+    result.push_str(&latency::format_reaction_summary(
+        &auctions,
+        bucket_ms.max(1) as i64,
+    ));
+    Ok(result)
+}
+
+/// Efficiency of each order's auction against its minimum and a reference
+/// quote, then the distribution across the orders. An order that cannot be
+/// read is reported and left out.
+async fn get_auction_efficiency(
+    order_ids: &[String],
+    bucket_bps: u64,
+    quotes: &efficiency::QuoteApi,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<String> {
+    let mut orders = Vec::new();
+    let mut result = String::new();
+    for order_id in order_ids {
+        let order = async {
+            let record = explorer.order_record(order_id).await?;
+            let auction_state_addr = serde_json::from_value::<MayanOrderResponse>(record.clone())
+                .context("Failed to parse JSON response")?
+                .auction_state_addr;
+            let (auction_state, _) =
+                get_and_parse_auction_state(&auction_state_addr, explorer, rpc).await?;
+            let input = efficiency::OrderInput::from_explorer_record(&record);
+            let quote = quotes.quote(&input).await;
+            Ok::<_, anyhow::Error>(efficiency::OrderEfficiency::new(
+                order_id,
+                &auction_state_addr,
+                input,
+                &auction_state,
+                quote,
+            ))
+        }
+        .await;
+        match order {
+            Ok(order) => {
+                result.push_str(&format!(
+                    "{}\n\n",
+                    efficiency::format_order_efficiency(&order)
+                ));
+                orders.push(order);
+            }
+            Err(e) => result.push_str(&format!(
+                "{} {}\n  {}: {:#}\n\n",
+                "Order".cyan(),
+                order_id,
+                "Error".red(),
+                e
+            )),
+        }
+    }
+    result.push_str(&efficiency::format_efficiency_summary(
+        &orders,
+        bucket_bps.max(1) as i64,
+    ));
+    Ok(result)
+}
+
+async fn get_my_bids(bidder: &str, limit: usize, rpc: &rpc::Rpc) -> Result<String> {
+    let bidder = Pubkey::from_str(bidder).context("Failed to parse bidder as Pubkey")?;
+
+    let context_slot = rpc.get_slot()?;
+    let (bids, scanned) = my_bids::scan_bids(rpc, &bidder, limit)?;
+    let groups = my_bids::group_by_auction(rpc, bids).await?;
+    Ok(my_bids::format_my_bids(
+        &bidder,
+        &groups,
+        scanned,
+        context_slot,
+    ))
+}
+
+/// Auction state and Swift order state addresses of an order. The order state
+/// comes from the explorer record when there is one, otherwise it is derived
+/// from the order hash while the auction state account still exists.
+async fn resolve_order_accounts(
+    input: &str,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<Vec<Pubkey>> {
+    let (auction_state_addr, record) = match Pubkey::from_str(input) {
+        Ok(_) => (input.to_string(), None),
+        Err(_) => {
+            let record = explorer.order_record(input).await?;
+            let order_data: MayanOrderResponse =
+                serde_json::from_value(record.clone()).context("Failed to parse JSON response")?;
+            (order_data.auction_state_addr, Some(record))
+        }
+    };
+    let auction_pubkey = Pubkey::from_str(&auction_state_addr)
+        .context("Failed to parse auction state address as Pubkey")?;
+
+    let mut accounts = vec![auction_pubkey];
+    let state_addr = record
+        .as_ref()
+        .and_then(|record| record.get("stateAddr"))
+        .and_then(|value| value.as_str())
+        .and_then(|addr| Pubkey::from_str(addr).ok());
+    match state_addr {
+        Some(state) => accounts.push(state),
+        None => {
+            if let Ok((account, _)) = rpc.get_account(&auction_pubkey) {
+                if let Ok(auction_state) = parse_auction_state_data(&account.data) {
+                    accounts.push(programs::derive_order_state(&auction_state.hash));
+                }
+            }
+        }
+    }
+    Ok(accounts)
+}
+
+async fn get_order_timeline(
+    input: &str,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<String> {
+    let accounts = resolve_order_accounts(input, explorer, rpc).await?;
+    let context_slot = rpc.get_slot()?;
+
+    let mut result = format!(
+        "{}: {}\n{}: {}\n",
+        "Auction State Address".green(),
+        accounts[0],
+        "Context Slot".green(),
+        context_slot
+    );
+    match accounts.get(1) {
+        Some(state) => result.push_str(&format!("{}: {}\n", "Order State Address".green(), state)),
+        None => result.push_str(&format!(
+            "{}: order state address unknown, only the auction state is scanned\n",
+            "Warning".yellow()
+        )),
+    }
+
+    let entries = timeline::collect_timeline(rpc, &accounts)?;
+    result.push_str(&format!("\n{}", timeline::format_timeline(&entries)));
+    Ok(result)
+}
+
+struct TrackOptions {
+    interval: u64,
+    timeout: u64,
+    onchain: bool,
+}
+
+/// Poll the explorer record (and optionally the AuctionState) of an order,
+/// printing each change, until a final state; `None` means the timeout was hit
+async fn track_order(
+    order_id: &str,
+    options: &TrackOptions,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<Option<track::Outcome>> {
+    let started = std::time::Instant::now();
+    let mut last_status: Option<String> = None;
+    let mut last_error: Option<String> = None;
+    let mut last_auction: Option<String> = None;
+    let mut auction_state_addr: Option<String> = None;
+
+    loop {
+        match explorer.order_record(order_id).await {
+            Ok(record) => {
+                last_error = None;
+                if auction_state_addr.is_none() {
+                    auction_state_addr = record
+                        .get("auctionStateAddr")
+                        .and_then(|value| value.as_str())
+                        .filter(|addr| Pubkey::from_str(addr).is_ok())
+                        .map(|addr| addr.to_string());
+                }
+
+                let (status, outcome) = track::record_status(&record);
+                if last_status.as_ref() != Some(&status) {
+                    println!(
+                        "{}",
+                        track::progress_line(&format!(
+                            "{}: {} ({})",
+                            "Status".green(),
+                            status,
+                            outcome.label()
+                        ))
+                    );
+                    last_status = Some(status);
+                }
+                if outcome.is_final() {
+                    println!(
+                        "\n{}: {} after {}",
+                        "Final State".green(),
+                        outcome.label(),
+                        timeline::format_duration(started.elapsed().as_secs() as i64)
+                    );
+                    return Ok(Some(outcome));
+                }
+            }
+            Err(e) => {
+                let message = e.to_string();
+                if last_error.as_ref() != Some(&message) {
+                    println!(
+                        "{}",
+                        track::progress_line(&format!("{}: {}", "Warning".yellow(), message))
+                    );
+                    last_error = Some(message);
+                }
+            }
+        }
+
+        if let (true, Some(addr)) = (options.onchain, &auction_state_addr) {
+            let (auction, context_slot) =
+                match get_and_parse_auction_state(addr, explorer, rpc).await {
+                    Ok((state, context_slot)) => (
+                        format!(
+                            "{}: {}, {}: {}, {}: {}",
+                            "Winner".green(),
+                            state.winner,
+                            "Amount Promised".green(),
+                            state.amount_promised,
+                            "Sequence Message".green(),
+                            state.seq_msg
+                        ),
+                        Some(context_slot),
+                    ),
+                    Err(_) => (
+                        format!("{}: not found or closed", "Auction State".yellow()),
+                        None,
+                    ),
+                };
+            if last_auction.as_ref() != Some(&auction) {
+                let slot = context_slot
+                    .map(|slot| format!(" ({}: {})", "Context Slot".green(), slot))
+                    .unwrap_or_default();
+                println!("{}", track::progress_line(&format!("{}{}", auction, slot)));
+                last_auction = Some(auction);
+            }
+        }
+
+        if options.timeout > 0 && started.elapsed().as_secs() >= options.timeout {
+            println!(
+                "\n{}: no final state after {}",
+                "Timed Out".red(),
+                timeline::format_duration(started.elapsed().as_secs() as i64)
+            );
+            return Ok(None);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(options.interval)).await;
+    }
+}
 
-    Ok(bids)
+struct BidOptions<'a> {
+    amount: u64,
+    keypair: &'a str,
+    compute_unit_limit: Option<u32>,
+    priority_fee: Option<u64>,
+    decimals: u32,
+    json: Option<&'a str>,
+    dry_run: bool,
+    yes: bool,
 }
 
-fn format_auction_state(auction_state: &AuctionState) -> String {
+async fn place_bid(
+    input: &str,
+    options: &BidOptions<'_>,
+    explorer: &explorer::ExplorerApi,
+    rpc: &rpc::Rpc,
+) -> Result<()> {
+    let keypair = read_keypair_file(options.keypair)
+        .map_err(|e| anyhow::anyhow!("Failed to read keypair file: {}", e))?;
+    let bidder = keypair.pubkey();
+
+    let auction_state_addr = resolve_auction_state_addr(input, explorer).await?;
+    let auction_pubkey = Pubkey::from_str(&auction_state_addr)
+        .context("Failed to parse auction state address as Pubkey")?;
+    let (auction_state, context_slot) =
+        get_and_parse_auction_state(&auction_state_addr, explorer, rpc).await?;
+    let (order_info, source) =
+        resolve_order_info(input, options.json, explorer, rpc, &auction_pubkey).await?;
+
+    println!("{}", format_auction_state(&auction_state, context_slot));
+    println!(
+        "\nBid Details:
+  {}: {}
+  {}: {}
+  {}: {} ({} UI)
+  {}: {} ({} UI)
+  {}: {} ({} UI)
+  {}: {}
+  {}: {}
+  {}: {}",
+        "Auction State Address".green(),
+        auction_state_addr,
+        "Bidder".green(),
+        bidder,
+        "Amount".green(),
+        options.amount,
+        order::format_ui_amount(options.amount, options.decimals),
+        "Current Amount Promised".green(),
+        auction_state.amount_promised,
+        order::format_ui_amount(auction_state.amount_promised, options.decimals),
+        "Amount Out Min".green(),
+        auction_state.amount_out_min,
+        order::format_ui_amount(auction_state.amount_out_min, options.decimals),
+        "Compute Unit Limit".green(),
+        options
+            .compute_unit_limit
+            .map(|limit| limit.to_string())
+            .unwrap_or_else(|| "Default".to_string()),
+        "Priority Fee".green(),
+        options
+            .priority_fee
+            .map(|price| format!("{} micro-lamports/CU", price))
+            .unwrap_or_else(|| "None".to_string()),
+        "Order Parameters".green(),
+        source
+    );
+
+    if order::compute_order_hash(&order_info) != auction_state.hash {
+        println!(
+            "{}: order hash does not match AuctionState.hash, the bid will be rejected",
+            "Warning".yellow()
+        );
+    }
+    if options.amount <= auction_state.amount_promised {
+        println!(
+            "{}: amount does not exceed the current amount promised",
+            "Warning".yellow()
+        );
+    }
+
+    let mut instructions =
+        bid::compute_budget_instructions(options.compute_unit_limit, options.priority_fee);
+    instructions.push(bid::build_bid_instruction(
+        &auction_pubkey,
+        &bidder,
+        &order_info,
+        options.amount,
+    )?);
+
+    if options.dry_run {
+        let (simulation, simulation_slot) =
+            bid::simulate_instructions(rpc, &instructions, &bidder)?;
+        println!("\n{}", bid::format_simulation(&simulation, simulation_slot));
+        return Ok(());
+    }
+
+    if !options.yes {
+        print!("\nSend this bid? [y/N]: ");
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("{}", "Aborted".yellow());
+            return Ok(());
+        }
+    }
+
+    let blockhash = rpc
+        .call(|client| Ok(client.get_latest_blockhash()?))
+        .context("Failed to get latest blockhash")?;
+    let transaction =
+        Transaction::new_signed_with_payer(&instructions, Some(&bidder), &[&keypair], blockhash);
+    // Resending the same signed transaction to another endpoint cannot land it twice
+    let signature = rpc
+        .call(|client| Ok(client.send_and_confirm_transaction(&transaction)?))
+        .context("Failed to send bid transaction")?;
+    println!("{}: {}", "Confirmed".green(), signature);
+
+    Ok(())
+}
+
+fn format_auction_state(auction_state: &AuctionState, context_slot: u64) -> String {
     format!(
         "Auction State Details:
   {}: {}
@@ -290,7 +1566,10 @@ fn format_auction_state(auction_state: &AuctionState) -> String {
   {}: {}
   {}: {}
   {}: {}
+  {}: {}
   {}: {}",
+        "Context Slot".green(),
+        context_slot,
         "Bump".green(),
         auction_state.bump,
         "Hash".green(),
@@ -312,6 +1591,95 @@ fn format_auction_state(auction_state: &AuctionState) -> String {
     )
 }
 
+/// Fetch and decode a transaction; with `fetch_idl`, the on-chain IDLs of
+/// invoked programs without one are loaded and the transaction decoded again
+fn decode_tx(
+    signature: &str,
+    fetch_idl: bool,
+    rpc: &rpc::Rpc,
+) -> Result<decoder::DecodedTransaction> {
+    let signature =
+        Signature::from_str(signature).context("Failed to parse transaction signature")?;
+    let transaction = decoder::fetch_transaction(rpc, &signature)?;
+    let decoded = decoder::decode_transaction(&transaction)?;
+    if !fetch_idl {
+        return Ok(decoded);
+    }
+
+    let mut program_ids = std::collections::BTreeSet::new();
+    decoder::program_ids(&decoded.instructions, &mut program_ids);
+    let mut fetched = false;
+    for program_id in program_ids {
+        let Ok(program_id) = Pubkey::from_str(&program_id) else {
+            continue;
+        };
+        if idl::get(&program_id).is_none() && idl::get_or_fetch(rpc, &program_id).is_some() {
+            fetched = true;
+        }
+    }
+    if fetched {
+        decoder::decode_transaction(&transaction)
+    } else {
+        Ok(decoded)
+    }
+}
+
+/// Decode an account with the IDL of its owner program, from --idl or fetched
+/// on-chain. Auction states decode without an IDL; other accounts are shown
+/// as hex.
+fn decode_account(address: &str, rpc: &rpc::Rpc) -> Result<String> {
+    let pubkey = Pubkey::from_str(address).context("Failed to parse account address as Pubkey")?;
+    let (account, context_slot) = rpc.get_account(&pubkey)?;
+    let mut result = format!(
+        "Account Details:\n  {}: {}\n  {}: {}\n  {}: {}\n  {}: {}\n  {}: {} bytes",
+        "Address".green(),
+        address,
+        "Context Slot".green(),
+        context_slot,
+        "Owner".green(),
+        account.owner,
+        "Lamports".green(),
+        account.lamports,
+        "Data Length".green(),
+        account.data.len()
+    );
+
+    if let Some(program_idl) = idl::get_or_fetch(rpc, &account.owner) {
+        if let Some((name, fields)) = program_idl.decode_account(&account.data) {
+            let fields = fields.with_context(|| format!("Failed to decode {} account", name))?;
+            result.push_str(&format!(
+                "\n  {}: {} ({} IDL)\n  {}:",
+                "Type".green(),
+                name,
+                program_idl.name,
+                "Fields".green()
+            ));
+            for (field, value) in fields {
+                result.push_str(&format!("\n    {}: {}", field.green(), value));
+            }
+            return Ok(result);
+        }
+    }
+
+    if account.owner == programs::auction_program_id() {
+        let auction_state = parse_auction_state_data(&account.data)?;
+        result.push_str(&format!(
+            "\n  {}: AuctionState (built-in)\n\n{}",
+            "Type".green(),
+            format_auction_state(&auction_state, context_slot)
+        ));
+        return Ok(result);
+    }
+
+    result.push_str(&format!(
+        "\n  {}: Unknown (no IDL for the owner program)\n  {}: {}",
+        "Type".green(),
+        "Data".green(),
+        hex::encode(account.data)
+    ));
+    Ok(result)
+}
+
 fn format_bid_history(bids: &[BidEntry]) -> String {
     if bids.is_empty() {
         return format!("{}: No bids found", "Bid History".yellow());
@@ -322,7 +1690,15 @@ fn format_bid_history(bids: &[BidEntry]) -> String {
     for (i, bid) in bids.iter().enumerate() {
         let (diff_str, status_str) = if i > 0 && bid.bid_amount > 0 && bids[i - 1].bid_amount > 0 {
             let diff = bid.bid_amount as i128 - bids[i - 1].bid_amount as i128;
-            let status = format!("  {}: {}", "Status".green(), if bid.failed { "Failed".red() } else { "Success".green() });
+            let status = format!(
+                "  {}: {}",
+                "Status".green(),
+                if bid.failed {
+                    "Failed".red()
+                } else {
+                    "Success".green()
+                }
+            );
             if diff >= 0 {
                 (format!("+{}", diff).blue().to_string(), status)
             } else {
@@ -364,11 +1740,87 @@ fn format_bid_history(bids: &[BidEntry]) -> String {
                 "".to_string()
             }
         ));
+        result.push_str(&fees::format_bid_fees(&bid.fees));
+        if let Some(same_slot) = &bid.same_slot {
+            result.push_str(&format!(
+                "\n  {}: bid {} of {} in the slot{}",
+                "Same Slot".yellow(),
+                same_slot.rank,
+                same_slot.bids,
+                same_slot
+                    .block_position
+                    .map(|(index, count)| format!(
+                        " (transaction {} of {} in the block)",
+                        index + 1,
+                        count
+                    ))
+                    .unwrap_or_default()
+            ));
+        }
+        if let Some(error) = &bid.error {
+            result.push_str(&format!(
+                "\n  {}: {}",
+                "Error".red(),
+                errors::format_decoded_error(error)
+            ));
+        }
+        if !bid.error_logs.is_empty() {
+            result.push_str(&format!("\n  {}:", "Logs".green()));
+            for log in &bid.error_logs {
+                result.push_str(&format!("\n    {}", log));
+            }
+        }
+        result.push_str(&events::format_events(&bid.events, "  "));
     }
 
     result
 }
 
+/// Failure reasons of each bidder's failed bids; empty when no bid failed
+fn format_failure_summary(bids: &[BidEntry]) -> String {
+    let failed: Vec<&BidEntry> = bids.iter().filter(|bid| bid.failed).collect();
+    if failed.is_empty() {
+        return String::new();
+    }
+
+    let mut by_bidder: std::collections::BTreeMap<&str, Vec<String>> =
+        std::collections::BTreeMap::new();
+    for bid in &failed {
+        let reason = bid
+            .error
+            .as_ref()
+            .map(errors::failure_reason)
+            .unwrap_or_else(|| "Unknown".to_string());
+        by_bidder
+            .entry(bid.bidder.as_str())
+            .or_default()
+            .push(reason);
+    }
+
+    let mut result = format!(
+        "{}: {} of {} bids failed",
+        "Failure Reasons".red(),
+        failed.len(),
+        bids.len()
+    );
+    for (bidder, mut reasons) in by_bidder {
+        result.push_str(&format!("\n  {}: {} failed", bidder, reasons.len()));
+        reasons.sort();
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for reason in reasons {
+            match counts.last_mut() {
+                Some((last, count)) if *last == reason => *count += 1,
+                _ => counts.push((reason, 1)),
+            }
+        }
+        counts.sort_by_key(|c| std::cmp::Reverse(c.1));
+        for (reason, count) in counts {
+            result.push_str(&format!("\n    {}: {}", reason, count));
+        }
+    }
+    result
+}
+
 fn decode_base58(input: &str, format: &str) -> Result<()> {
     let decoded = bs58::decode(input)
         .into_vec()
@@ -532,10 +1984,62 @@ fn from_bytes32(input: &str, input_format: &str, output_format: &str) -> Result<
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let cluster = match programs::Cluster::from_str(&cli.cluster) {
+        Ok(cluster) => cluster,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    match programs::ProgramIds::new(
+        cli.auction_program_id.as_deref(),
+        cli.swift_program_id.as_deref(),
+    ) {
+        Ok(ids) => programs::configure(ids),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    for spec in &cli.idl {
+        match idl::load_file(spec) {
+            Ok((program_id, program_idl)) => idl::register(program_id, program_idl),
+            Err(e) => {
+                eprintln!("Error: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    let traffic = match transport::TrafficMode::new(cli.record.as_deref(), cli.replay.as_deref()) {
+        Ok(traffic) => traffic,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let rpc_options = match rpc::RpcOptions::new(
+        cli.commitment.as_deref(),
+        cli.min_context_slot,
+        cli.rpc_timeout,
+        traffic.clone(),
+        cluster,
+    ) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let http = transport::http_api(&traffic);
+    let explorer_api_url = cli
+        .explorer_api
+        .clone()
+        .or(cluster.explorer_api_url().map(String::from));
+    let explorer = explorer::ExplorerApi::new(http.clone(), explorer_api_url);
 
     match cli.command {
         Commands::GetAuctionStateAddress { order_id } => {
-            match get_auction_state_addr(&order_id).await {
+            match get_auction_state_addr(&order_id, &explorer).await {
                 Ok(auction_state_addr) => {
                     println!(
                         "{}: {}",
@@ -549,10 +2053,38 @@ async fn main() -> Result<()> {
                 }
             }
         }
-        Commands::GetAuctionState { input, rpc_url } => {
-            match get_and_parse_auction_state(&input, &rpc_url).await {
-                Ok(auction_state) => {
-                    println!("{}", format_auction_state(&auction_state));
+        Commands::GetAuctionState {
+            input,
+            quorum: true,
+            rpc_url,
+        } => match get_auction_state_quorum(
+            &input,
+            &explorer,
+            &rpc::Rpc::new(&rpc_url, &rpc_options),
+        )
+        .await
+        {
+            Ok((output, data_agrees)) => {
+                println!("{}", output);
+                if !data_agrees {
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::GetAuctionState { input, rpc_url, .. } => {
+            match get_and_parse_auction_state(
+                &input,
+                &explorer,
+                &rpc::Rpc::new(&rpc_url, &rpc_options),
+            )
+            .await
+            {
+                Ok((auction_state, context_slot)) => {
+                    println!("{}", format_auction_state(&auction_state, context_slot));
                 }
                 Err(e) => {
                     eprintln!("Error: {}", e);
@@ -560,7 +2092,11 @@ async fn main() -> Result<()> {
                 }
             }
         }
-        Commands::GetBids { input, rpc_url } => {
+        Commands::GetBids {
+            input,
+            strict,
+            rpc_url,
+        } => {
             // Determine if input is an order ID or auction state address
             let auction_state_addr = match Pubkey::from_str(&input) {
                 Ok(_) => {
@@ -569,7 +2105,7 @@ async fn main() -> Result<()> {
                 }
                 Err(_) => {
                     // Input is likely an order ID, fetch auction state address from API
-                    match get_auction_state_addr(&input).await {
+                    match get_auction_state_addr(&input, &explorer).await {
                         Ok(addr) => addr,
                         Err(e) => {
                             eprintln!("Error getting auction state address: {}", e);
@@ -579,9 +2115,39 @@ async fn main() -> Result<()> {
                 }
             };
 
-            match get_bid_history(&auction_state_addr, &rpc_url).await {
-                Ok(bids) => {
-                    println!("{}", format_bid_history(&bids));
+            let rpc = rpc::Rpc::new(&rpc_url, &rpc_options);
+            match read_bid_history(&auction_state_addr, &rpc).await {
+                Ok(history) => {
+                    let bids = &history.bids;
+                    println!("{}: {}", "Context Slot".green(), history.context_slot);
+                    println!("{}", format_bid_history(bids));
+                    let fee_summary = fees::format_fee_summary(bids);
+                    if !fee_summary.is_empty() {
+                        println!("\n{}", fee_summary);
+                    }
+                    let failures = format_failure_summary(bids);
+                    if !failures.is_empty() {
+                        println!("\n{}", failures);
+                    }
+
+                    let consistent =
+                        match reconcile_bid_history(&auction_state_addr, &history, &rpc) {
+                            Ok(reconciliation) => {
+                                println!("\n{}", reconcile::format_reconciliation(&reconciliation));
+                                reconciliation.is_consistent()
+                            }
+                            Err(e) => {
+                                println!(
+                                    "\n{}: auction state unavailable: {}",
+                                    "Reconciliation".yellow(),
+                                    e
+                                );
+                                false
+                            }
+                        };
+                    if strict && !consistent {
+                        std::process::exit(5);
+                    }
                 }
                 Err(e) => {
                     eprintln!("Error: {}", e);
@@ -629,6 +2195,402 @@ async fn main() -> Result<()> {
                 std::process::exit(1);
             }
         }
+        Commands::DecodeVaa { input, format } => {
+            match vaa::read_vaa_input(&input, &format).and_then(|bytes| vaa::parse_vaa(&bytes)) {
+                Ok(vaa) => {
+                    println!("{}", vaa::format_vaa(&vaa));
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::ComputeOrderHash {
+            params,
+            json,
+            order,
+            expect,
+            auction,
+            rpc_url,
+        } => {
+            match compute_order_hash_report(
+                params,
+                json.as_deref(),
+                order.as_deref(),
+                expect.as_deref(),
+                auction.as_deref(),
+                &explorer,
+                &rpc::Rpc::new(&rpc_url, &rpc_options),
+            )
+            .await
+            {
+                Ok((output, all_match)) => {
+                    println!("{}", output);
+                    if !all_match {
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::SimulateBid {
+            input,
+            amount,
+            bidder,
+            json,
+            rpc_url,
+        } => match simulate_bid_report(
+            &input,
+            amount,
+            &bidder,
+            json.as_deref(),
+            &explorer,
+            &rpc::Rpc::new(&rpc_url, &rpc_options),
+        )
+        .await
+        {
+            Ok(output) => {
+                println!("{}", output);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Bid {
+            input,
+            amount,
+            keypair,
+            compute_unit_limit,
+            priority_fee,
+            decimals,
+            json,
+            dry_run,
+            yes,
+            rpc_url,
+        } => {
+            let options = BidOptions {
+                amount,
+                keypair: &keypair,
+                compute_unit_limit,
+                priority_fee,
+                decimals,
+                json: json.as_deref(),
+                dry_run,
+                yes,
+            };
+            if let Err(e) = place_bid(
+                &input,
+                &options,
+                &explorer,
+                &rpc::Rpc::new(&rpc_url, &rpc_options),
+            )
+            .await
+            {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::DecodeTx {
+            signature,
+            fetch_idl,
+            rpc_url,
+        } => {
+            match decode_tx(
+                &signature,
+                fetch_idl,
+                &rpc::Rpc::new(&rpc_url, &rpc_options),
+            ) {
+                Ok(transaction) => {
+                    println!("{}", decoder::format_decoded_transaction(&transaction));
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::DecodeAccount { address, rpc_url } => {
+            match decode_account(&address, &rpc::Rpc::new(&rpc_url, &rpc_options)) {
+                Ok(output) => println!("{}", output),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Snapshot {
+            input,
+            out,
+            rpc_url,
+        } => match take_auction_snapshot(&input, &explorer, &rpc::Rpc::new(&rpc_url, &rpc_options))
+            .await
+        {
+            Ok(snapshot) => {
+                let result = match &out {
+                    Some(path) => snapshot.save(path).map(|_| {
+                        format!(
+                            "{}: {} at slot {} to {}",
+                            "Saved Snapshot".green(),
+                            snapshot.address,
+                            snapshot.slot,
+                            path
+                        )
+                    }),
+                    None => snapshot.to_json(),
+                };
+                match result {
+                    Ok(output) => println!("{}", output),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Diff {
+            before,
+            after,
+            rpc_url,
+        } => {
+            let rpc = rpc::Rpc::new(&rpc_url, &rpc_options);
+            let snapshots = match load_or_take_snapshot(&before, &explorer, &rpc).await {
+                Ok(before) => load_or_take_snapshot(&after, &explorer, &rpc)
+                    .await
+                    .map(|after| (before, after)),
+                Err(e) => Err(e),
+            };
+            match snapshots {
+                Ok((before, after)) => {
+                    println!("{}", snapshot::format_diff(&before, &after));
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Timeline { input, rpc_url } => {
+            match get_order_timeline(&input, &explorer, &rpc::Rpc::new(&rpc_url, &rpc_options))
+                .await
+            {
+                Ok(output) => {
+                    println!("{}", output);
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Track {
+            order_id,
+            interval,
+            timeout,
+            onchain,
+            rpc_url,
+        } => {
+            let options = TrackOptions {
+                interval,
+                timeout,
+                onchain,
+            };
+            match track_order(
+                &order_id,
+                &options,
+                &explorer,
+                &rpc::Rpc::new(&rpc_url, &rpc_options),
+            )
+            .await
+            {
+                Ok(Some(outcome)) => std::process::exit(outcome.exit_code()),
+                Ok(None) => std::process::exit(track::TIMEOUT_EXIT_CODE),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Events {
+            address,
+            limit,
+            rpc_url,
+        } => match get_account_events(&address, limit, &rpc::Rpc::new(&rpc_url, &rpc_options)) {
+            Ok((transactions, context_slot)) => {
+                println!("{}", format_account_events(&transactions, context_slot));
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Efficiency {
+            order_ids,
+            quote_api,
+            bucket_bps,
+            rpc_url,
+        } => match get_auction_efficiency(
+            &order_ids,
+            bucket_bps,
+            &efficiency::QuoteApi::new(http.clone(), quote_api),
+            &explorer,
+            &rpc::Rpc::new(&rpc_url, &rpc_options),
+        )
+        .await
+        {
+            Ok(output) => {
+                println!("{}", output);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Latency {
+            inputs,
+            bucket_ms,
+            rpc_url,
+        } => match get_bid_latency(
+            &inputs,
+            bucket_ms,
+            &explorer,
+            &rpc::Rpc::new(&rpc_url, &rpc_options),
+        )
+        .await
+        {
+            Ok(output) => {
+                println!("{}", output);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::MyBids {
+            bidder,
+            limit,
+            rpc_url,
+        } => match get_my_bids(&bidder, limit, &rpc::Rpc::new(&rpc_url, &rpc_options)).await {
+            Ok(output) => {
+                println!("{}", output);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::GetAuctionVaa {
+            input,
+            rpc_url,
+            wormhole_api,
+        } => match get_auction_vaa(
+            &input,
+            &explorer,
+            &rpc::Rpc::new(&rpc_url, &rpc_options),
+            http.as_ref(),
+            &wormhole_api,
+        )
+        .await
+        {
+            Ok(output) => {
+                println!("{}", output);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Exporter {
+            listen,
+            watch,
+            interval,
+            recent,
+            max_auctions,
+            rpc_url,
+        } => {
+            let watch = match watch
+                .iter()
+                .map(|address| {
+                    Pubkey::from_str(address)
+                        .with_context(|| format!("Failed to parse watched account {}", address))
+                })
+                .collect::<Result<Vec<_>>>()
+            {
+                Ok(watch) => watch,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let options = exporter::ExporterOptions {
+                listen,
+                watch,
+                interval,
+                recent,
+                max_auctions: max_auctions.max(1),
+            };
+            let rpc = rpc::Rpc::new(&rpc_url, &rpc_options);
+            if let Err(e) = exporter::run(rpc, options).await {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Alert {
+            bidders,
+            webhook,
+            interval,
+            recent,
+            retries,
+            dead_letter,
+            rpc_url,
+        } => {
+            let bidders = match bidders
+                .iter()
+                .map(|bidder| {
+                    Pubkey::from_str(bidder)
+                        .with_context(|| format!("Failed to parse bidder {}", bidder))
+                })
+                .collect::<Result<Vec<_>>>()
+            {
+                Ok(bidders) => bidders,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let options = alert::AlertOptions {
+                bidders,
+                webhook,
+                interval,
+                recent,
+                retries,
+                dead_letter,
+            };
+            let rpc = rpc::Rpc::new(&rpc_url, &rpc_options);
+            if let Err(e) = alert::run(rpc, options).await {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Serve {
+            listen,
+            cache_ttl,
+            rpc_url,
+        } => {
+            let rpc = rpc::Rpc::new(&rpc_url, &rpc_options);
+            if let Err(e) = serve::serve(&listen, rpc, explorer, cache_ttl).await {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
     }
 
     Ok(())
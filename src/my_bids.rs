@@ -0,0 +1,314 @@
+use crate::decoder::{self, account, arg, auction_instructions};
+use crate::programs;
+use crate::reconcile::highest_bid;
+use crate::rpc::Rpc;
+use crate::AuctionState;
+use anyhow::{Context, Result};
+use colored::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Signatures requested per page; the most an RPC node returns at once
+const PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub struct OwnBid {
+    pub signature: String,
+    pub slot: u64,
+    /// `None` when the amount could not be decoded from the instruction
+    pub amount: Option<u64>,
+    pub failed: bool,
+}
+
+/// How an auction went for the bidder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won,
+    Lost,
+    /// Every bid transaction failed
+    Failed,
+    /// The auction state is closed and its bid history shows no successful bid,
+    /// so the winner is not known
+    Unknown,
+}
+
+impl Outcome {
+    pub fn label(self) -> ColoredString {
+        match self {
+            Outcome::Won => "Won".green(),
+            Outcome::Lost => "Lost".yellow(),
+            Outcome::Failed => "Failed".red(),
+            Outcome::Unknown => "Unknown".dimmed(),
+        }
+    }
+}
+
+/// The bidder's bids on one auction, with the auction's current state
+pub struct AuctionBids {
+    pub auction: String,
+    /// Newest first, as the signatures were listed
+    pub bids: Vec<OwnBid>,
+    /// `None` once the account is closed
+    pub state: Option<AuctionState>,
+    /// Bidder and amount of the highest successful bid in the auction's bid
+    /// history, read once the account is closed: the auction program only
+    /// accepts higher bids, so that bid won
+    pub closed_winner: Option<(String, u64)>,
+}
+
+impl AuctionBids {
+    /// Highest amount among the successful bids
+    pub fn best_bid(&self) -> Option<u64> {
+        self.bids
+            .iter()
+            .filter(|bid| !bid.failed)
+            .filter_map(|bid| bid.amount)
+            .max()
+    }
+
+    pub fn outcome(&self, bidder: &Pubkey) -> Outcome {
+        if self.bids.iter().all(|bid| bid.failed) {
+            return Outcome::Failed;
+        }
+        match (&self.state, &self.closed_winner) {
+            (Some(state), _) if state.winner == *bidder => Outcome::Won,
+            (Some(_), _) => Outcome::Lost,
+            (None, Some((winner, _))) if *winner == bidder.to_string() => Outcome::Won,
+            (None, Some(_)) => Outcome::Lost,
+            (None, None) => Outcome::Unknown,
+        }
+    }
+
+    /// Winning `amount_promised` minus our best bid: what it would have taken
+    /// to match the winner
+    pub fn gap(&self) -> Option<i128> {
+        let amount_promised = match (&self.state, &self.closed_winner) {
+            (Some(state), _) => state.amount_promised,
+            (None, Some((_, amount))) => *amount,
+            (None, None) => return None,
+        };
+        Some(amount_promised as i128 - self.best_bid()? as i128)
+    }
+
+    /// False while the result has not been posted to Wormhole
+    pub fn is_final(&self) -> bool {
+        self.state.as_ref().is_none_or(|state| state.seq_msg != 0)
+    }
+}
+
+/// Bid instructions of `bidder` in its `limit` most recent transactions,
+/// paging back through its signatures. Returns the bids with their auction
+/// state address, newest first, and the number of transactions scanned.
+pub fn scan_bids(
+    rpc: &Rpc,
+    bidder: &Pubkey,
+    limit: usize,
+) -> Result<(Vec<(String, OwnBid)>, usize)> {
+    let auction_program = programs::auction_program_id().to_string();
+    let bidder_str = bidder.to_string();
+    let mut bids = Vec::new();
+    let mut scanned = 0;
+    let mut before: Option<String> = None;
+
+    while scanned < limit {
+        let page_size = PAGE_SIZE.min(limit - scanned);
+        let page = rpc
+            .get_signatures_for_address_page(bidder, before.as_deref(), None, page_size)
+            .with_context(|| format!("Failed to get signatures for {}", bidder))?;
+
+        for sig_info in &page {
+            let signature = Signature::from_str(&sig_info.signature)?;
+            let transaction =
+                decoder::decode_transaction(&decoder::fetch_transaction(rpc, &signature)?)?;
+            let mut instructions = Vec::new();
+            auction_instructions(
+                &transaction.instructions,
+                &auction_program,
+                &mut instructions,
+            );
+
+            for instruction in instructions {
+                if instruction.name.as_deref() != Some("bid") {
+                    continue;
+                }
+                let driver = account(instruction, "driver").unwrap_or(transaction.signer.as_str());
+                let Some(auction) = account(instruction, "auction_state") else {
+                    continue;
+                };
+                if driver != bidder_str {
+                    continue;
+                }
+                bids.push((
+                    auction.to_string(),
+                    OwnBid {
+                        signature: transaction.signature.clone(),
+                        slot: transaction.slot,
+                        amount: arg(instruction, "amount_bid").and_then(|a| a.parse().ok()),
+                        failed: transaction.err.is_some(),
+                    },
+                ));
+            }
+        }
+
+        scanned += page.len();
+        if page.len() < page_size {
+            break;
+        }
+        before = page.last().map(|sig_info| sig_info.signature.clone());
+    }
+
+    Ok((bids, scanned))
+}
+
+/// Group bids by auction, most recently bid on first, and read each auction's
+/// state, or the bid history of those whose account is closed
+pub async fn group_by_auction(rpc: &Rpc, bids: Vec<(String, OwnBid)>) -> Result<Vec<AuctionBids>> {
+    let mut groups: Vec<AuctionBids> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (auction, bid) in bids {
+        let position = *index.entry(auction.clone()).or_insert_with(|| {
+            groups.push(AuctionBids {
+                auction,
+                bids: Vec::new(),
+                state: None,
+                closed_winner: None,
+            });
+            groups.len() - 1
+        });
+        groups[position].bids.push(bid);
+    }
+
+    for group in &mut groups {
+        let pubkey = Pubkey::from_str(&group.auction)
+            .context("Failed to parse auction state address as Pubkey")?;
+        match rpc.get_optional_account(&pubkey)? {
+            (Some(account), _) => {
+                group.state = Some(
+                    crate::parse_auction_state_data(&account.data)
+                        .with_context(|| format!("Failed to parse auction {}", group.auction))?,
+                );
+            }
+            (None, _) => {
+                let history = crate::read_bid_history(&group.auction, rpc).await?;
+                group.closed_winner = highest_bid(&history.bids, None)
+                    .map(|bid| (bid.bidder.clone(), bid.bid_amount));
+            }
+        }
+    }
+    Ok(groups)
+}
+
+fn format_amount(amount: Option<u64>) -> String {
+    amount
+        .map(|amount| amount.to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+pub fn format_my_bids(
+    bidder: &Pubkey,
+    groups: &[AuctionBids],
+    scanned: usize,
+    context_slot: u64,
+) -> String {
+    let mut result = format!(
+        "{}: {}\n{}: {}\n{}: {} transactions\n",
+        "Bidder".green(),
+        bidder,
+        "Context Slot".green(),
+        context_slot,
+        "Scanned".green(),
+        scanned
+    );
+    if groups.is_empty() {
+        result.push_str(&format!("{}: No bids found", "My Bids".yellow()));
+        return result;
+    }
+
+    let bid_count: usize = groups.iter().map(|group| group.bids.len()).sum();
+    let mut counts: Vec<(Outcome, usize)> = Vec::new();
+    for outcome in [
+        Outcome::Won,
+        Outcome::Lost,
+        Outcome::Failed,
+        Outcome::Unknown,
+    ] {
+        let count = groups
+            .iter()
+            .filter(|group| group.outcome(bidder) == outcome)
+            .count();
+        if count > 0 {
+            counts.push((outcome, count));
+        }
+    }
+    result.push_str(&format!(
+        "{}: {} bids in {} auctions ({})\n",
+        "My Bids".green(),
+        bid_count,
+        groups.len(),
+        counts
+            .iter()
+            .map(|(outcome, count)| format!("{} {}", count, outcome.label()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+
+    for group in groups {
+        let failed = group.bids.iter().filter(|bid| bid.failed).count();
+        result.push_str(&format!(
+            "\n{} {}: {}{}\n  {}: {}{}\n  {}: {}",
+            "Auction".cyan(),
+            group.auction,
+            group.outcome(bidder).label(),
+            if group.is_final() {
+                String::new()
+            } else {
+                " (in progress)".dimmed().to_string()
+            },
+            "Bids".green(),
+            group.bids.len(),
+            if failed > 0 {
+                format!(" ({} failed)", failed)
+            } else {
+                String::new()
+            },
+            "Best Bid".green(),
+            format_amount(group.best_bid())
+        ));
+        if let Some(state) = &group.state {
+            result.push_str(&format!(
+                "\n  {}: {}\n  {}: {}",
+                "Winner".green(),
+                state.winner,
+                "Amount Promised".green(),
+                state.amount_promised
+            ));
+        } else if let Some((winner, amount)) = &group.closed_winner {
+            result.push_str(&format!(
+                "\n  {}: {} (from bid history)\n  {}: {}",
+                "Winner".green(),
+                winner,
+                "Amount Promised".green(),
+                amount
+            ));
+        }
+        if let Some(gap) = group.gap() {
+            let gap = if gap > 0 {
+                format!("+{}", gap).red().to_string()
+            } else {
+                gap.to_string()
+            };
+            result.push_str(&format!("\n  {}: {}", "Gap".green(), gap));
+        }
+        let last = &group.bids[0];
+        result.push_str(&format!(
+            "\n  {}: {} ({}: {})\n",
+            "Last Bid".green(),
+            last.signature,
+            "Slot".green(),
+            last.slot
+        ));
+    }
+    result
+}
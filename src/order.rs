@@ -0,0 +1,419 @@
+use anyhow::{Context, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+use clap::Args;
+use colored::*;
+use serde::Deserialize;
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Swift order parameters, in the field order used both by the Borsh layout
+/// of the auction program's `OrderInfo` and by the packed order hash encoding
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct OrderInfo {
+    pub trader: [u8; 32],
+    pub chain_source: u16,
+    pub token_in: [u8; 32],
+    pub addr_dest: [u8; 32],
+    pub chain_dest: u16,
+    pub token_out: [u8; 32],
+    pub amount_out_min: u64,
+    pub gas_drop: u64,
+    pub fee_cancel: u64,
+    pub fee_refund: u64,
+    pub deadline: u64,
+    pub penalty_period: u16,
+    pub referrer_addr: [u8; 32],
+    pub referrer_bps: u8,
+    pub protocol_bps: u8,
+    pub auction_mode: u8,
+    pub key_rnd: [u8; 32],
+}
+
+/// Order parameters as given on the command line or in a JSON file (camelCase keys).
+/// Addresses may be base58 (Solana) or hex (EVM, left-padded to 32 bytes).
+/// Amounts are normalized integer units, as stored on-chain.
+#[derive(Debug, Clone, Default, Args, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderParams {
+    /// Trader address
+    #[arg(long)]
+    pub trader: Option<String>,
+    /// Wormhole chain ID of the source chain
+    #[arg(long)]
+    pub chain_source: Option<u16>,
+    /// Input token address
+    #[arg(long)]
+    pub token_in: Option<String>,
+    /// Destination address
+    #[arg(long)]
+    pub addr_dest: Option<String>,
+    /// Wormhole chain ID of the destination chain
+    #[arg(long)]
+    pub chain_dest: Option<u16>,
+    /// Output token address
+    #[arg(long)]
+    pub token_out: Option<String>,
+    /// Minimum amount out (normalized units)
+    #[arg(long)]
+    pub amount_out_min: Option<u64>,
+    /// Gas drop (normalized units)
+    #[arg(long)]
+    pub gas_drop: Option<u64>,
+    /// Cancel fee (normalized units)
+    #[arg(long)]
+    pub fee_cancel: Option<u64>,
+    /// Refund fee (normalized units)
+    #[arg(long)]
+    pub fee_refund: Option<u64>,
+    /// Order deadline (unix seconds)
+    #[arg(long)]
+    pub deadline: Option<u64>,
+    /// Penalty period (seconds)
+    #[arg(long)]
+    pub penalty_period: Option<u16>,
+    /// Referrer address
+    #[arg(long)]
+    pub referrer_addr: Option<String>,
+    /// Referrer fee in bps
+    #[arg(long)]
+    pub referrer_bps: Option<u8>,
+    /// Protocol fee in bps
+    #[arg(long)]
+    pub protocol_bps: Option<u8>,
+    /// Auction mode
+    #[arg(long)]
+    pub auction_mode: Option<u8>,
+    /// Random key chosen by the trader (32 bytes hex)
+    #[arg(long)]
+    pub key_rnd: Option<String>,
+}
+
+impl OrderParams {
+    /// Fill every field that is unset in `self` from `other`
+    pub fn or(self, other: OrderParams) -> OrderParams {
+        OrderParams {
+            trader: self.trader.or(other.trader),
+            chain_source: self.chain_source.or(other.chain_source),
+            token_in: self.token_in.or(other.token_in),
+            addr_dest: self.addr_dest.or(other.addr_dest),
+            chain_dest: self.chain_dest.or(other.chain_dest),
+            token_out: self.token_out.or(other.token_out),
+            amount_out_min: self.amount_out_min.or(other.amount_out_min),
+            gas_drop: self.gas_drop.or(other.gas_drop),
+            fee_cancel: self.fee_cancel.or(other.fee_cancel),
+            fee_refund: self.fee_refund.or(other.fee_refund),
+            deadline: self.deadline.or(other.deadline),
+            penalty_period: self.penalty_period.or(other.penalty_period),
+            referrer_addr: self.referrer_addr.or(other.referrer_addr),
+            referrer_bps: self.referrer_bps.or(other.referrer_bps),
+            protocol_bps: self.protocol_bps.or(other.protocol_bps),
+            auction_mode: self.auction_mode.or(other.auction_mode),
+            key_rnd: self.key_rnd.or(other.key_rnd),
+        }
+    }
+
+    pub fn from_json_file(path: &str) -> Result<OrderParams> {
+        let contents = std::fs::read_to_string(path).context("Failed to read order JSON file")?;
+        serde_json::from_str(&contents).context("Failed to parse order JSON file")
+    }
+
+    /// Map an explorer order record onto order parameters. The explorer reports
+    /// amounts in UI units, so they are normalized to at most 8 decimals as the
+    /// Swift program does.
+    pub fn from_explorer_record(record: &Value) -> Result<OrderParams> {
+        let out_decimals = record_u64(record, &["toTokenDecimals", "toToken.decimals"])
+            .unwrap_or(8)
+            .min(8) as u32;
+        let in_decimals = record_u64(record, &["fromTokenDecimals", "fromToken.decimals"])
+            .unwrap_or(8)
+            .min(8) as u32;
+
+        Ok(OrderParams {
+            trader: record_str(record, &["trader"]),
+            chain_source: record_narrow(record, &["sourceChain"])?,
+            token_in: record_str(record, &["swiftInputContract", "fromTokenAddress"]),
+            addr_dest: record_str(record, &["destAddress"]),
+            chain_dest: record_narrow(record, &["destChain"])?,
+            token_out: record_str(record, &["toTokenAddress"]),
+            amount_out_min: record_amount(record, &["minAmountOut"], out_decimals)?,
+            gas_drop: record_amount(record, &["gasDrop"], 8)?,
+            fee_cancel: record_amount(
+                record,
+                &["swiftCancelFee", "redeemRelayerFee"],
+                in_decimals,
+            )?,
+            fee_refund: record_amount(
+                record,
+                &["swiftRefundFee", "refundRelayerFee"],
+                in_decimals,
+            )?,
+            deadline: record_u64(record, &["deadline"]),
+            penalty_period: record_narrow(record, &["penaltyPeriod"])?,
+            referrer_addr: record_str(record, &["referrerAddress"]),
+            referrer_bps: record_narrow(record, &["referrerBps"])?,
+            protocol_bps: record_narrow(record, &["mayanBps", "protocolBps"])?,
+            auction_mode: record_narrow(record, &["auctionMode"])?,
+            key_rnd: record_str(record, &["randomKey", "keyRnd"]),
+        })
+    }
+
+    pub fn to_order_info(&self) -> Result<OrderInfo> {
+        fn require<T: Clone>(value: &Option<T>, name: &str) -> Result<T> {
+            value
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Missing order parameter: {}", name))
+        }
+
+        Ok(OrderInfo {
+            trader: parse_address32(&require(&self.trader, "trader")?)?,
+            chain_source: require(&self.chain_source, "chain-source")?,
+            token_in: parse_address32(&require(&self.token_in, "token-in")?)?,
+            addr_dest: parse_address32(&require(&self.addr_dest, "addr-dest")?)?,
+            chain_dest: require(&self.chain_dest, "chain-dest")?,
+            token_out: parse_address32(&require(&self.token_out, "token-out")?)?,
+            amount_out_min: require(&self.amount_out_min, "amount-out-min")?,
+            gas_drop: self.gas_drop.unwrap_or(0),
+            fee_cancel: self.fee_cancel.unwrap_or(0),
+            fee_refund: self.fee_refund.unwrap_or(0),
+            deadline: require(&self.deadline, "deadline")?,
+            penalty_period: self.penalty_period.unwrap_or(0),
+            referrer_addr: match &self.referrer_addr {
+                Some(addr) => parse_address32(addr)?,
+                None => [0u8; 32],
+            },
+            referrer_bps: self.referrer_bps.unwrap_or(0),
+            protocol_bps: self.protocol_bps.unwrap_or(0),
+            auction_mode: self.auction_mode.unwrap_or(0),
+            key_rnd: parse_address32(&require(&self.key_rnd, "key-rnd")?)?,
+        })
+    }
+}
+
+fn record_field<'a>(record: &'a Value, names: &[&str]) -> Option<&'a Value> {
+    names.iter().find_map(|name| {
+        let value = name
+            .split('.')
+            .try_fold(record, |value, key| value.get(key))?;
+        (!value.is_null()).then_some(value)
+    })
+}
+
+/// First of the `names` fields (dotted paths) set in an explorer record, as a
+/// string
+pub fn record_str(record: &Value, names: &[&str]) -> Option<String> {
+    record_field(record, names).map(|value| match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    })
+}
+
+pub fn record_u64(record: &Value, names: &[&str]) -> Option<u64> {
+    record_field(record, names).and_then(|value| match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    })
+}
+
+/// A field that must fit a narrower integer type; out-of-range values are an
+/// error rather than truncated, as they would give a wrong order hash
+fn record_narrow<T: TryFrom<u64>>(record: &Value, names: &[&str]) -> Result<Option<T>> {
+    record_u64(record, names)
+        .map(|value| {
+            T::try_from(value).map_err(|_| {
+                anyhow::anyhow!("Explorer field {} is out of range: {}", names[0], value)
+            })
+        })
+        .transpose()
+}
+
+fn record_amount(record: &Value, names: &[&str], decimals: u32) -> Result<Option<u64>> {
+    record_str(record, names)
+        .map(|amount| parse_ui_amount(&amount, decimals))
+        .transpose()
+}
+
+/// Convert a UI amount such as "6.41865924" into integer units with `decimals`
+/// decimals, truncating any extra precision
+pub fn parse_ui_amount(amount: &str, decimals: u32) -> Result<u64> {
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let mut fraction: String = fraction.chars().take(decimals as usize).collect();
+    while fraction.len() < decimals as usize {
+        fraction.push('0');
+    }
+    format!("{}{}", whole, fraction)
+        .parse::<u64>()
+        .with_context(|| format!("Failed to parse amount '{}'", amount))
+}
+
+/// Format integer units with `decimals` decimals, e.g. 641865924 at 8 -> "6.41865924"
+pub fn format_ui_amount(amount: u64, decimals: u32) -> String {
+    // Formatted as digits rather than by dividing, as 10^decimals overflows a
+    // u64 past 19 decimals
+    let digits = format!("{:0>width$}", amount, width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Parse an address into 32 bytes: 0x-prefixed hex (left-padded, as EVM
+/// addresses are) or a base58 Solana public key
+pub fn parse_address32(input: &str) -> Result<[u8; 32]> {
+    if let Some(hex_str) = input.strip_prefix("0x") {
+        let bytes = hex::decode(hex_str).context("Failed to decode hex address")?;
+        if bytes.len() > 32 {
+            return Err(anyhow::anyhow!(
+                "Address is too long: {} bytes. Maximum is 32 bytes",
+                bytes.len()
+            ));
+        }
+        let mut result = [0u8; 32];
+        result[32 - bytes.len()..].copy_from_slice(&bytes);
+        return Ok(result);
+    }
+
+    Pubkey::from_str(input)
+        .map(|pubkey| pubkey.to_bytes())
+        .with_context(|| format!("Failed to parse address '{}' as hex or base58", input))
+}
+
+/// Order fields for display, with addresses rendered for their chain
+pub fn order_fields(order: &OrderInfo) -> Vec<(String, String)> {
+    let address = crate::vaa::format_address;
+    vec![
+        (
+            "trader".to_string(),
+            address(order.chain_source, &order.trader),
+        ),
+        ("chain_source".to_string(), order.chain_source.to_string()),
+        (
+            "token_in".to_string(),
+            address(order.chain_source, &order.token_in),
+        ),
+        (
+            "addr_dest".to_string(),
+            address(order.chain_dest, &order.addr_dest),
+        ),
+        ("chain_dest".to_string(), order.chain_dest.to_string()),
+        (
+            "token_out".to_string(),
+            address(order.chain_dest, &order.token_out),
+        ),
+        (
+            "amount_out_min".to_string(),
+            order.amount_out_min.to_string(),
+        ),
+        ("gas_drop".to_string(), order.gas_drop.to_string()),
+        ("fee_cancel".to_string(), order.fee_cancel.to_string()),
+        ("fee_refund".to_string(), order.fee_refund.to_string()),
+        ("deadline".to_string(), order.deadline.to_string()),
+        (
+            "penalty_period".to_string(),
+            order.penalty_period.to_string(),
+        ),
+        (
+            "referrer_addr".to_string(),
+            hex::encode(order.referrer_addr),
+        ),
+        ("referrer_bps".to_string(), order.referrer_bps.to_string()),
+        ("protocol_bps".to_string(), order.protocol_bps.to_string()),
+        ("auction_mode".to_string(), order.auction_mode.to_string()),
+        ("key_rnd".to_string(), hex::encode(order.key_rnd)),
+    ]
+}
+
+/// Fields of the order hash preimage: name, Solidity type and big-endian bytes
+pub fn encode_order_fields(order: &OrderInfo) -> Vec<(&'static str, &'static str, Vec<u8>)> {
+    vec![
+        ("Trader", "bytes32", order.trader.to_vec()),
+        (
+            "Source Chain",
+            "uint16",
+            order.chain_source.to_be_bytes().to_vec(),
+        ),
+        ("Token In", "bytes32", order.token_in.to_vec()),
+        ("Destination Address", "bytes32", order.addr_dest.to_vec()),
+        (
+            "Destination Chain",
+            "uint16",
+            order.chain_dest.to_be_bytes().to_vec(),
+        ),
+        ("Token Out", "bytes32", order.token_out.to_vec()),
+        (
+            "Amount Out Min",
+            "uint64",
+            order.amount_out_min.to_be_bytes().to_vec(),
+        ),
+        ("Gas Drop", "uint64", order.gas_drop.to_be_bytes().to_vec()),
+        (
+            "Cancel Fee",
+            "uint64",
+            order.fee_cancel.to_be_bytes().to_vec(),
+        ),
+        (
+            "Refund Fee",
+            "uint64",
+            order.fee_refund.to_be_bytes().to_vec(),
+        ),
+        ("Deadline", "uint64", order.deadline.to_be_bytes().to_vec()),
+        (
+            "Penalty Period",
+            "uint16",
+            order.penalty_period.to_be_bytes().to_vec(),
+        ),
+        ("Referrer Address", "bytes32", order.referrer_addr.to_vec()),
+        ("Referrer Bps", "uint8", vec![order.referrer_bps]),
+        ("Protocol Bps", "uint8", vec![order.protocol_bps]),
+        ("Auction Mode", "uint8", vec![order.auction_mode]),
+        ("Random Key", "bytes32", order.key_rnd.to_vec()),
+    ]
+}
+
+/// keccak256 over the tightly packed big-endian fields, as the Swift program
+/// (and `abi.encodePacked` on EVM) computes it
+pub fn compute_order_hash(order: &OrderInfo) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    for (_, _, bytes) in encode_order_fields(order) {
+        hasher.update(&bytes);
+    }
+    hasher.finalize().into()
+}
+
+/// Extract the order hash from a Swift order ID (`SWIFT_0x<hash>`) or a bare hex hash
+pub fn parse_order_hash(input: &str) -> Result<[u8; 32]> {
+    let hex_str = input.strip_prefix("SWIFT_").unwrap_or(input);
+    let hex_str = hex_str.strip_prefix("0x").unwrap_or(hex_str);
+    let bytes = hex::decode(hex_str).context("Failed to decode order hash")?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Order hash must be exactly 32 bytes"))
+}
+
+pub fn format_order_encoding(order: &OrderInfo, hash: &[u8; 32]) -> String {
+    let mut result = format!(
+        "{}: keccak256(packed big-endian fields, abi.encodePacked layout)",
+        "Encoding".green()
+    );
+    for (name, ty, bytes) in encode_order_fields(order) {
+        result.push_str(&format!(
+            "\n  {} ({}): {}",
+            name.green(),
+            ty,
+            hex::encode(bytes)
+        ));
+    }
+    result.push_str(&format!(
+        "\n{}: {}\n{}: SWIFT_0x{}",
+        "Order Hash".green(),
+        hex::encode(hash),
+        "Order ID".green(),
+        hex::encode(hash)
+    ));
+    result
+}
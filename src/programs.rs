@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Mayan Swift auction program on mainnet, owner of `AuctionState` accounts
+pub const MAINNET_AUCTION_PROGRAM_ID: Pubkey =
+    pubkey!("8QJmxZcEzwuYmCPy8XqgN2qzS2b6sN4pJLnQZ2vNkLoZ");
+/// Mayan Swift program on mainnet, owner of order state accounts
+pub const MAINNET_SWIFT_PROGRAM_ID: Pubkey =
+    pubkey!("BLZRi6frs4X4DNLw56V4EXai1b6QVESN1BhHBTYM9VcY");
+
+/// Wormhole chain ID of Solana
+pub const SOLANA_WORMHOLE_CHAIN_ID: u16 = 1;
+
+/// Solana cluster the CLI talks to, from the global `--cluster` flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Localnet,
+}
+
+impl FromStr for Cluster {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name {
+            "mainnet" => Ok(Cluster::Mainnet),
+            "devnet" => Ok(Cluster::Devnet),
+            "localnet" => Ok(Cluster::Localnet),
+            _ => Err(anyhow::anyhow!("Unknown cluster: {}", name)),
+        }
+    }
+}
+
+impl Cluster {
+    /// RPC endpoint used when neither `--rpc-url` nor `SOLANA_RPC_URL` is set
+    pub fn rpc_url(&self) -> &'static str {
+        match self {
+            Cluster::Mainnet => "https://api.mainnet-beta.solana.com",
+            Cluster::Devnet => "https://api.devnet.solana.com",
+            Cluster::Localnet => "http://127.0.0.1:8899",
+        }
+    }
+
+    /// The Mayan explorer only indexes mainnet orders
+    pub fn explorer_api_url(&self) -> Option<&'static str> {
+        match self {
+            Cluster::Mainnet => Some("https://explorer-api.mayan.finance"),
+            Cluster::Devnet | Cluster::Localnet => None,
+        }
+    }
+}
+
+/// IDs of the Swift programs in the configured deployment
+#[derive(Debug, Clone, Copy)]
+pub struct ProgramIds {
+    pub auction: Pubkey,
+    pub swift: Pubkey,
+}
+
+impl ProgramIds {
+    /// The mainnet IDs unless overridden; a test validator can load the
+    /// programs at their mainnet addresses, so every cluster defaults to them
+    pub fn new(auction: Option<&str>, swift: Option<&str>) -> Result<Self> {
+        let parse = |id: Option<&str>, default: Pubkey, name: &str| {
+            id.map(|id| {
+                Pubkey::from_str(id).with_context(|| format!("Failed to parse {} program ID", name))
+            })
+            .unwrap_or(Ok(default))
+        };
+        Ok(ProgramIds {
+            auction: parse(auction, MAINNET_AUCTION_PROGRAM_ID, "auction")?,
+            swift: parse(swift, MAINNET_SWIFT_PROGRAM_ID, "Swift")?,
+        })
+    }
+}
+
+static PROGRAM_IDS: OnceLock<ProgramIds> = OnceLock::new();
+
+/// Set the program IDs for the rest of the process; called once at startup,
+/// before anything is decoded
+pub fn configure(ids: ProgramIds) {
+    let _ = PROGRAM_IDS.set(ids);
+}
+
+/// Swift auction program, owner of `AuctionState` accounts
+pub fn auction_program_id() -> Pubkey {
+    PROGRAM_IDS
+        .get()
+        .map_or(MAINNET_AUCTION_PROGRAM_ID, |ids| ids.auction)
+}
+
+/// Swift program, owner of order state accounts
+pub fn swift_program_id() -> Pubkey {
+    PROGRAM_IDS
+        .get()
+        .map_or(MAINNET_SWIFT_PROGRAM_ID, |ids| ids.swift)
+}
+
+/// Wormhole emitter PDA of a program, which signs the messages it posts
+pub fn derive_emitter(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"emitter"], program_id).0
+}
+
+/// Swift order state PDA, keyed by the order hash
+pub fn derive_order_state(order_hash: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"STATE", order_hash], &swift_program_id()).0
+}
@@ -0,0 +1,237 @@
+use crate::{AuctionState, BidEntry, BidHistory, BID_HISTORY_LIMIT};
+use colored::*;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// A way the bid history disagrees with the auction result
+#[derive(Debug, Clone)]
+pub enum Mismatch {
+    /// `AuctionState.winner` has no successful bid in the history
+    WinnerWithoutBid { winner: Pubkey },
+    /// The winner's highest visible bid is not `amount_promised`
+    WrongAmount {
+        bid_amount: u64,
+        amount_promised: u64,
+        signature: String,
+    },
+    /// A successful bid above `amount_promised` from someone other than the
+    /// winner
+    HigherBidNotWinning {
+        bidder: String,
+        amount: u64,
+        signature: String,
+    },
+    /// Only the newest `BID_HISTORY_LIMIT` of the auction's signatures were
+    /// read, so older bids may be missing
+    HistoryTruncated { read: usize, total: usize },
+}
+
+impl Mismatch {
+    fn describe(&self) -> String {
+        match self {
+            Mismatch::WinnerWithoutBid { winner } => {
+                format!("winner {} has no successful bid in the history", winner)
+            }
+            Mismatch::WrongAmount {
+                bid_amount,
+                amount_promised,
+                signature,
+            } => format!(
+                "winner's highest bid is {} but {} is promised ({})",
+                bid_amount, amount_promised, signature
+            ),
+            Mismatch::HigherBidNotWinning {
+                bidder,
+                amount,
+                signature,
+            } => format!(
+                "{} bid {} above the amount promised but did not win ({})",
+                bidder, amount, signature
+            ),
+            Mismatch::HistoryTruncated { read, total } => format!(
+                "history truncated: read the newest {} of {} signatures",
+                read, total
+            ),
+        }
+    }
+}
+
+/// Where the auction result compared with the bid history comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultSource {
+    AuctionState,
+    /// The account is closed; the `AuctionClosed` event names the winner
+    AuctionClosed,
+    /// The account is closed and its `AuctionClosed` event was not read; the
+    /// highest successful bid won, as the auction program only accepts higher
+    /// bids
+    HighestBid,
+}
+
+impl ResultSource {
+    fn label(self) -> &'static str {
+        match self {
+            ResultSource::AuctionState => "AuctionState",
+            ResultSource::AuctionClosed => "AuctionClosed event (account closed)",
+            ResultSource::HighestBid => "highest bid (account closed)",
+        }
+    }
+}
+
+/// Highest successful bid of the history compared with the auction result
+pub struct Reconciliation {
+    pub source: ResultSource,
+    /// `None` while `AuctionState.winner` is unset
+    pub winner: Option<Pubkey>,
+    pub amount_promised: u64,
+    /// Bidder, amount and signature of the highest successful bid; the
+    /// earliest one on ties, as the auction program only accepts higher bids
+    pub highest: Option<(String, u64, String)>,
+    pub signatures_read: usize,
+    pub signatures: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Reconciliation {
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Highest successful bid, of `bidder` when given
+pub fn highest_bid<'a>(bids: &'a [BidEntry], bidder: Option<&str>) -> Option<&'a BidEntry> {
+    let mut highest: Option<&BidEntry> = None;
+    for bid in bids {
+        if bid.failed || bidder.is_some_and(|bidder| bid.bidder != bidder) {
+            continue;
+        }
+        match highest {
+            Some(highest) if bid.bid_amount <= highest.bid_amount => {}
+            _ => highest = Some(bid),
+        }
+    }
+    highest
+}
+
+pub fn reconcile(history: &BidHistory, state: &AuctionState) -> Reconciliation {
+    let winner = Some(state.winner).filter(|winner| *winner != Pubkey::default());
+    reconcile_result(
+        history,
+        winner,
+        state.amount_promised,
+        ResultSource::AuctionState,
+    )
+}
+
+/// Reconcile an auction whose account is closed, against its `AuctionClosed`
+/// event or else its highest successful bid
+pub fn reconcile_closed(history: &BidHistory) -> Reconciliation {
+    let (winner, amount_promised, source) = match &history.closed {
+        Some((winner, amount_promised)) => (
+            Pubkey::from_str(winner).ok(),
+            *amount_promised,
+            ResultSource::AuctionClosed,
+        ),
+        None => {
+            let highest = highest_bid(&history.bids, None);
+            (
+                highest.and_then(|bid| Pubkey::from_str(&bid.bidder).ok()),
+                highest.map(|bid| bid.bid_amount).unwrap_or(0),
+                ResultSource::HighestBid,
+            )
+        }
+    };
+    reconcile_result(history, winner, amount_promised, source)
+}
+
+fn reconcile_result(
+    history: &BidHistory,
+    winner: Option<Pubkey>,
+    amount_promised: u64,
+    source: ResultSource,
+) -> Reconciliation {
+    let read = history.signatures.min(BID_HISTORY_LIMIT);
+    let mut mismatches = Vec::new();
+
+    if history.signatures > read {
+        mismatches.push(Mismatch::HistoryTruncated {
+            read,
+            total: history.signatures,
+        });
+    }
+    if let Some(winner) = winner {
+        match highest_bid(&history.bids, Some(winner.to_string().as_str())) {
+            None => mismatches.push(Mismatch::WinnerWithoutBid { winner }),
+            Some(bid) if bid.bid_amount != amount_promised => {
+                mismatches.push(Mismatch::WrongAmount {
+                    bid_amount: bid.bid_amount,
+                    amount_promised,
+                    signature: bid.signature.clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    let highest = highest_bid(&history.bids, None);
+    if let Some(bid) = highest {
+        let won = winner.is_some_and(|winner| winner.to_string() == bid.bidder);
+        if !won && bid.bid_amount > amount_promised {
+            mismatches.push(Mismatch::HigherBidNotWinning {
+                bidder: bid.bidder.clone(),
+                amount: bid.bid_amount,
+                signature: bid.signature.clone(),
+            });
+        }
+    }
+
+    Reconciliation {
+        source,
+        winner,
+        amount_promised,
+        highest: highest.map(|bid| (bid.bidder.clone(), bid.bid_amount, bid.signature.clone())),
+        signatures_read: read,
+        signatures: history.signatures,
+        mismatches,
+    }
+}
+
+pub fn format_reconciliation(reconciliation: &Reconciliation) -> String {
+    let mut result = if reconciliation.is_consistent() {
+        format!("{}: {}", "Reconciliation".green(), "OK".green())
+    } else {
+        format!(
+            "{}: {} mismatches",
+            "Reconciliation".red(),
+            reconciliation.mismatches.len()
+        )
+    };
+    result.push_str(&format!(
+        "\n  {}: {}\n  {}: {}\n  {}: {}\n  {}: {}\n  {}: {} of {}",
+        "Result From".green(),
+        reconciliation.source.label(),
+        "Winner".green(),
+        reconciliation
+            .winner
+            .map(|winner| winner.to_string())
+            .unwrap_or_else(|| "None".to_string()),
+        "Amount Promised".green(),
+        reconciliation.amount_promised,
+        "Highest Bid".green(),
+        reconciliation
+            .highest
+            .as_ref()
+            .map(|(bidder, amount, signature)| format!("{} by {} ({})", amount, bidder, signature))
+            .unwrap_or_else(|| "None".to_string()),
+        "Signatures Read".green(),
+        reconciliation.signatures_read,
+        reconciliation.signatures
+    ));
+    for mismatch in &reconciliation.mismatches {
+        result.push_str(&format!(
+            "\n  {}: {}",
+            "Mismatch".red(),
+            mismatch.describe()
+        ));
+    }
+    result
+}
@@ -0,0 +1,320 @@
+use crate::programs::Cluster;
+use crate::transport::{self, TrafficMode};
+use anyhow::{Context, Result};
+use serde_json::json;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcBlockConfig, RpcContextConfig, RpcSignaturesForAddressConfig,
+    RpcSimulateTransactionConfig, RpcTransactionConfig,
+};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::{
+    RpcConfirmedTransactionStatusWithSignature, RpcSimulateTransactionResult,
+};
+use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, TransactionDetails, UiTransactionEncoding,
+};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Read options shared by every command, from the global `--commitment`,
+/// `--min-context-slot`, `--rpc-timeout`, `--record`/`--replay` and `--cluster` flags
+#[derive(Clone)]
+pub struct RpcOptions {
+    /// `None` keeps the defaults: finalized for accounts, confirmed for transactions
+    pub commitment: Option<CommitmentConfig>,
+    pub min_context_slot: Option<Slot>,
+    /// Per-request timeout, after which the next endpoint is tried
+    pub timeout: Duration,
+    pub traffic: TrafficMode,
+    /// Provides the endpoint when no `--rpc-url` is given
+    pub cluster: Cluster,
+}
+
+impl RpcOptions {
+    pub fn new(
+        commitment: Option<&str>,
+        min_context_slot: Option<Slot>,
+        timeout_secs: u64,
+        traffic: TrafficMode,
+        cluster: Cluster,
+    ) -> Result<Self> {
+        let commitment = commitment
+            .map(|level| {
+                CommitmentLevel::from_str(level)
+                    .map(|commitment| CommitmentConfig { commitment })
+                    .map_err(|_| anyhow::anyhow!("Invalid commitment level: {}", level))
+            })
+            .transpose()?;
+        Ok(RpcOptions {
+            commitment,
+            min_context_slot,
+            timeout: Duration::from_secs(timeout_secs),
+            traffic,
+            cluster,
+        })
+    }
+}
+
+/// One or more Solana RPC endpoints with the read options applied to every
+/// request. Requests go to the endpoint that last answered and fail over to
+/// the next one on errors or timeouts.
+pub struct Rpc {
+    endpoints: Vec<(String, RpcClient)>,
+    current: AtomicUsize,
+    options: RpcOptions,
+}
+
+/// Result of reading an account from one endpoint in quorum mode
+pub struct EndpointRead {
+    pub url: String,
+    pub result: Result<(Account, Slot)>,
+}
+
+impl Rpc {
+    pub fn new(urls: &[String], options: &RpcOptions) -> Self {
+        let mut urls: Vec<&str> = urls
+            .iter()
+            .map(|url| url.trim())
+            .filter(|url| !url.is_empty())
+            .collect();
+        if urls.is_empty() {
+            urls.push(options.cluster.rpc_url());
+        }
+        let endpoints = urls
+            .into_iter()
+            .map(|url| {
+                (
+                    url.to_string(),
+                    transport::rpc_client(
+                        url,
+                        options.timeout,
+                        options.commitment.unwrap_or_default(),
+                        &options.traffic,
+                    ),
+                )
+            })
+            .collect();
+        Rpc {
+            endpoints,
+            current: AtomicUsize::new(0),
+            options: options.clone(),
+        }
+    }
+
+    pub fn endpoint_count(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    /// Run a request against the current endpoint, then each of the others in
+    /// turn until one succeeds; the error of the last endpoint is returned
+    pub fn call<T>(&self, request: impl Fn(&RpcClient) -> Result<T>) -> Result<T> {
+        if self.endpoints.is_empty() {
+            return Err(anyhow::anyhow!("No RPC endpoint configured"));
+        }
+
+        let start = self.current.load(Ordering::Relaxed);
+        let mut last_error = None;
+        for offset in 0..self.endpoints.len() {
+            let index = (start + offset) % self.endpoints.len();
+            let (url, client) = &self.endpoints[index];
+            match request(client) {
+                Ok(value) => {
+                    self.current.store(index, Ordering::Relaxed);
+                    return Ok(value);
+                }
+                Err(e) => {
+                    if self.endpoints.len() > 1 {
+                        eprintln!("Warning: RPC endpoint {} failed: {}", url, e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    /// Transaction history is only served at confirmed or finalized
+    fn history_commitment(&self) -> CommitmentConfig {
+        match self.options.commitment {
+            Some(commitment) if commitment.is_at_least_confirmed() => commitment,
+            _ => CommitmentConfig::confirmed(),
+        }
+    }
+
+    fn account_config(&self, client: &RpcClient) -> RpcAccountInfoConfig {
+        RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: None,
+            commitment: Some(client.commitment()),
+            min_context_slot: self.options.min_context_slot,
+        }
+    }
+
+    /// Fetch an account together with the context slot it was read at
+    pub fn get_account(&self, pubkey: &Pubkey) -> Result<(Account, Slot)> {
+        let (account, slot) = self.get_optional_account(pubkey)?;
+        let account = account.ok_or_else(|| anyhow::anyhow!("Account {} not found", pubkey))?;
+        Ok((account, slot))
+    }
+
+    /// Like `get_account`, but `None` when the endpoint confirms the account
+    /// does not exist, as for a closed account; only request failures are errors
+    pub fn get_optional_account(&self, pubkey: &Pubkey) -> Result<(Option<Account>, Slot)> {
+        let response = self
+            .call(|client| Ok(client.get_account_with_config(pubkey, self.account_config(client))?))
+            .context("Failed to fetch account data from Solana")?;
+        Ok((response.value, response.context.slot))
+    }
+
+    /// Read an account from every endpoint, to compare what each one serves
+    pub fn get_account_from_all(&self, pubkey: &Pubkey) -> Vec<EndpointRead> {
+        self.endpoints
+            .iter()
+            .map(|(url, client)| {
+                let result = client
+                    .get_account_with_config(pubkey, self.account_config(client))
+                    .map_err(anyhow::Error::from)
+                    .and_then(|response| {
+                        let account = response
+                            .value
+                            .ok_or_else(|| anyhow::anyhow!("Account {} not found", pubkey))?;
+                        Ok((account, response.context.slot))
+                    });
+                EndpointRead {
+                    url: url.clone(),
+                    result,
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_signatures_for_address(
+        &self,
+        pubkey: &Pubkey,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        self.call(|client| {
+            Ok(client.send(
+                RpcRequest::GetSignaturesForAddress,
+                json!([
+                    pubkey.to_string(),
+                    RpcSignaturesForAddressConfig {
+                        commitment: Some(self.history_commitment()),
+                        min_context_slot: self.options.min_context_slot,
+                        ..RpcSignaturesForAddressConfig::default()
+                    }
+                ]),
+            )?)
+        })
+        .context("Failed to get signatures for address")
+    }
+
+    /// One page of up to `limit` signatures, newest first, older than `before`
+    /// and newer than `until` when given
+    pub fn get_signatures_for_address_page(
+        &self,
+        pubkey: &Pubkey,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        self.call(|client| {
+            Ok(client.send(
+                RpcRequest::GetSignaturesForAddress,
+                json!([
+                    pubkey.to_string(),
+                    RpcSignaturesForAddressConfig {
+                        before: before.map(String::from),
+                        until: until.map(String::from),
+                        limit: Some(limit),
+                        commitment: Some(self.history_commitment()),
+                        min_context_slot: self.options.min_context_slot,
+                    }
+                ]),
+            )?)
+        })
+        .context("Failed to get signatures for address")
+    }
+
+    pub fn get_transaction(
+        &self,
+        signature: &Signature,
+        encoding: UiTransactionEncoding,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+        self.call(|client| {
+            Ok(client.get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(encoding),
+                    max_supported_transaction_version: Some(0),
+                    commitment: Some(self.history_commitment()),
+                },
+            )?)
+        })
+        .context("Failed to fetch transaction")
+    }
+
+    /// Signatures of every transaction in a block, in the order they were
+    /// executed
+    pub fn get_block_signatures(&self, slot: Slot) -> Result<Vec<String>> {
+        let block = self
+            .call(|client| {
+                Ok(client.get_block_with_config(
+                    slot,
+                    RpcBlockConfig {
+                        encoding: None,
+                        transaction_details: Some(TransactionDetails::Signatures),
+                        rewards: Some(false),
+                        commitment: Some(self.history_commitment()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )?)
+            })
+            .with_context(|| format!("Failed to fetch block {}", slot))?;
+        Ok(block.signatures.unwrap_or_default())
+    }
+
+    /// Current slot at the commitment of transaction history reads, reported as
+    /// the context slot of reads that do not return one (signature listings)
+    pub fn get_slot(&self) -> Result<Slot> {
+        self.call(|client| {
+            Ok(client.send(
+                RpcRequest::GetSlot,
+                json!([RpcContextConfig {
+                    commitment: Some(self.history_commitment()),
+                    min_context_slot: self.options.min_context_slot,
+                }]),
+            )?)
+        })
+        .context("Failed to get slot")
+    }
+
+    /// Simulate a transaction; the result comes with the context slot it ran at
+    pub fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> Result<(RpcSimulateTransactionResult, Slot)> {
+        let response = self
+            .call(|client| {
+                Ok(client.simulate_transaction_with_config(
+                    transaction,
+                    RpcSimulateTransactionConfig {
+                        commitment: Some(client.commitment()),
+                        min_context_slot: self.options.min_context_slot,
+                        ..config.clone()
+                    },
+                )?)
+            })
+            .context("Failed to simulate transaction")?;
+        Ok((response.value, response.context.slot))
+    }
+}
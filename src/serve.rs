@@ -0,0 +1,221 @@
+use crate::events::DecodedEvent;
+use crate::explorer::ExplorerApi;
+use crate::rpc::Rpc;
+use crate::snapshot::SnapshotState;
+use crate::BidEntry;
+use anyhow::{Context, Result};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Clients and caches shared by every request, so connections to the RPC
+/// endpoints and the explorer are pooled across requests
+struct ServerState {
+    rpc: Rpc,
+    explorer: ExplorerApi,
+    cache_ttl: Duration,
+    /// Responses by request path, served again until `cache_ttl` has passed
+    responses: Mutex<HashMap<String, (Instant, Value)>>,
+    /// Auction state address of each order ID; it never changes once assigned
+    auction_addresses: Mutex<HashMap<String, String>>,
+}
+
+/// An error response; bad input is the client's fault, anything else is a
+/// failure of the RPC or explorer behind the server
+enum ApiError {
+    BadRequest(String),
+    Upstream(anyhow::Error),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ApiError::Upstream(e) => (StatusCode::BAD_GATEWAY, format!("{:#}", e)),
+        };
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        ApiError::Upstream(e)
+    }
+}
+
+type ApiResult = std::result::Result<Json<Value>, ApiError>;
+
+impl ServerState {
+    fn cached(&self, key: &str) -> Option<Value> {
+        let responses = self.responses.lock().unwrap();
+        responses
+            .get(key)
+            .filter(|(fetched, _)| fetched.elapsed() < self.cache_ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn store(&self, key: &str, value: &Value) {
+        let mut responses = self.responses.lock().unwrap();
+        responses.retain(|_, (fetched, _)| fetched.elapsed() < self.cache_ttl);
+        responses.insert(key.to_string(), (Instant::now(), value.clone()));
+    }
+
+    async fn auction_address(&self, order_id: &str) -> Result<String> {
+        if let Some(address) = self.auction_addresses.lock().unwrap().get(order_id) {
+            return Ok(address.clone());
+        }
+        let address = crate::get_auction_state_addr(order_id, &self.explorer).await?;
+        self.auction_addresses
+            .lock()
+            .unwrap()
+            .insert(order_id.to_string(), address.clone());
+        Ok(address)
+    }
+
+    async fn auction_json(&self, address: &str) -> Result<Value> {
+        let (state, context_slot) =
+            crate::get_and_parse_auction_state(address, &self.explorer, &self.rpc).await?;
+        Ok(json!({
+            "address": address,
+            "context_slot": context_slot,
+            "state": SnapshotState::from_auction_state(&state),
+        }))
+    }
+
+    async fn bids_json(&self, address: &str) -> Result<Value> {
+        let (bids, context_slot) = crate::get_bid_history(address, &self.rpc).await?;
+        Ok(json!({
+            "address": address,
+            "context_slot": context_slot,
+            "bids": bids.iter().map(bid_json).collect::<Vec<_>>(),
+        }))
+    }
+}
+
+fn event_json(event: &DecodedEvent) -> Value {
+    let fields: serde_json::Map<String, Value> = event
+        .fields
+        .iter()
+        .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+        .collect();
+    json!({
+        "program_id": event.program_id,
+        "name": event.name,
+        "fields": fields,
+    })
+}
+
+fn bid_json(bid: &BidEntry) -> Value {
+    json!({
+        "signature": bid.signature,
+        "bidder": bid.bidder,
+        "amount": bid.bid_amount,
+        "slot": bid.slot,
+        "timestamp": bid.timestamp,
+        "failed": bid.failed,
+        "error": bid.error,
+        "error_logs": bid.error_logs,
+        "fees": {
+            "unit_limit": bid.fees.unit_limit,
+            "unit_price": bid.fees.unit_price,
+            "units_consumed": bid.fees.units_consumed,
+            "fee": bid.fees.fee,
+            "priority_fee": bid.fees.priority_fee(),
+            "effective_unit_price": bid.fees.effective_unit_price(),
+            "jito_tip": bid.fees.jito_tip,
+        },
+        "same_slot": bid.same_slot.as_ref().map(|same_slot| json!({
+            "rank": same_slot.rank,
+            "bids": same_slot.bids,
+            "block_index": same_slot.block_position.map(|(index, _)| index),
+            "block_transactions": same_slot.block_position.map(|(_, count)| count),
+        })),
+        "events": bid.events.iter().map(event_json).collect::<Vec<_>>(),
+    })
+}
+
+fn parse_address(address: &str) -> std::result::Result<(), ApiError> {
+    Pubkey::from_str(address)
+        .map(|_| ())
+        .map_err(|_| ApiError::BadRequest(format!("Invalid auction state address: {}", address)))
+}
+
+/// Serve the cached response for `key`, or produce and cache a new one
+async fn cached_or<F>(state: &ServerState, key: String, fetch: F) -> ApiResult
+where
+    F: std::future::Future<Output = Result<Value>>,
+{
+    if let Some(value) = state.cached(&key) {
+        return Ok(Json(value));
+    }
+    let value = fetch.await?;
+    state.store(&key, &value);
+    Ok(Json(value))
+}
+
+async fn order(State(state): State<Arc<ServerState>>, Path(id): Path<String>) -> ApiResult {
+    let key = format!("/orders/{}", id);
+    cached_or(&state, key, state.explorer.order_record(&id)).await
+}
+
+async fn order_auction(State(state): State<Arc<ServerState>>, Path(id): Path<String>) -> ApiResult {
+    let address = state.auction_address(&id).await?;
+    let key = format!("/auctions/{}", address);
+    cached_or(&state, key, state.auction_json(&address)).await
+}
+
+async fn order_bids(State(state): State<Arc<ServerState>>, Path(id): Path<String>) -> ApiResult {
+    let address = state.auction_address(&id).await?;
+    let key = format!("/auctions/{}/bids", address);
+    cached_or(&state, key, state.bids_json(&address)).await
+}
+
+async fn auction(State(state): State<Arc<ServerState>>, Path(address): Path<String>) -> ApiResult {
+    parse_address(&address)?;
+    let key = format!("/auctions/{}", address);
+    cached_or(&state, key, state.auction_json(&address)).await
+}
+
+async fn auction_bids(
+    State(state): State<Arc<ServerState>>,
+    Path(address): Path<String>,
+) -> ApiResult {
+    parse_address(&address)?;
+    let key = format!("/auctions/{}/bids", address);
+    cached_or(&state, key, state.bids_json(&address)).await
+}
+
+/// Serve the JSON endpoints on `listen` until the process is stopped
+pub async fn serve(listen: &str, rpc: Rpc, explorer: ExplorerApi, cache_ttl: u64) -> Result<()> {
+    let state = Arc::new(ServerState {
+        rpc,
+        explorer,
+        cache_ttl: Duration::from_secs(cache_ttl),
+        responses: Mutex::new(HashMap::new()),
+        auction_addresses: Mutex::new(HashMap::new()),
+    });
+
+    let app = Router::new()
+        .route("/orders/{id}", get(order))
+        .route("/orders/{id}/auction", get(order_auction))
+        .route("/orders/{id}/bids", get(order_bids))
+        .route("/auctions/{address}", get(auction))
+        .route("/auctions/{address}/bids", get(auction_bids))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", listen))?;
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app)
+        .await
+        .context("HTTP server failed")
+}
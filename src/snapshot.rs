@@ -0,0 +1,161 @@
+use crate::AuctionState;
+use anyhow::{Context, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+/// A decoded `AuctionState` as of a slot, in the form saved by `snapshot`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuctionSnapshot {
+    pub address: String,
+    /// Slot the account was read at
+    pub slot: u64,
+    /// Unix time the snapshot was taken
+    pub timestamp: i64,
+    pub state: SnapshotState,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotState {
+    pub bump: u8,
+    pub hash: String,
+    pub initializer: String,
+    pub close_epoch: u64,
+    pub amount_out_min: u64,
+    pub winner: String,
+    pub amount_promised: u64,
+    pub valid_from: u64,
+    pub seq_msg: u64,
+}
+
+/// A snapshot field value; numbers are kept apart so diffs can show the change
+enum FieldValue {
+    Number(u64),
+    Text(String),
+}
+
+impl SnapshotState {
+    pub fn from_auction_state(state: &AuctionState) -> Self {
+        SnapshotState {
+            bump: state.bump,
+            hash: hex::encode(state.hash),
+            initializer: state.initializer.to_string(),
+            close_epoch: state.close_epoch,
+            amount_out_min: state.amount_out_min,
+            winner: state.winner.to_string(),
+            amount_promised: state.amount_promised,
+            valid_from: state.valid_from,
+            seq_msg: state.seq_msg,
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, FieldValue)> {
+        vec![
+            ("bump", FieldValue::Number(self.bump as u64)),
+            ("hash", FieldValue::Text(self.hash.clone())),
+            ("initializer", FieldValue::Text(self.initializer.clone())),
+            ("close_epoch", FieldValue::Number(self.close_epoch)),
+            ("amount_out_min", FieldValue::Number(self.amount_out_min)),
+            ("winner", FieldValue::Text(self.winner.clone())),
+            ("amount_promised", FieldValue::Number(self.amount_promised)),
+            ("valid_from", FieldValue::Number(self.valid_from)),
+            ("seq_msg", FieldValue::Number(self.seq_msg)),
+        ]
+    }
+}
+
+impl AuctionSnapshot {
+    pub fn new(address: &str, slot: u64, state: &AuctionState) -> Self {
+        AuctionSnapshot {
+            address: address.to_string(),
+            slot,
+            timestamp: chrono::Utc::now().timestamp(),
+            state: SnapshotState::from_auction_state(state),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read snapshot file {}", path))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse snapshot file {}", path))
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        std::fs::write(path, self.to_json()?)
+            .with_context(|| format!("Failed to write snapshot file {}", path))
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize snapshot")
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} ({}: {}, {}: {})",
+            self.address,
+            "Slot".green(),
+            self.slot,
+            "Taken".green(),
+            chrono::DateTime::from_timestamp(self.timestamp, 0)
+                .map(|time| time.to_rfc3339())
+                .unwrap_or_else(|| self.timestamp.to_string())
+        )
+    }
+}
+
+fn format_change(before: &FieldValue, after: &FieldValue) -> Option<String> {
+    match (before, after) {
+        (FieldValue::Number(a), FieldValue::Number(b)) if a != b => {
+            let delta = *b as i128 - *a as i128;
+            Some(format!("{} -> {} ({:+})", a, b, delta))
+        }
+        (FieldValue::Text(a), FieldValue::Text(b)) if a != b => Some(format!("{} -> {}", a, b)),
+        _ => None,
+    }
+}
+
+/// Field-by-field comparison of two snapshots, `before` first
+pub fn format_diff(before: &AuctionSnapshot, after: &AuctionSnapshot) -> String {
+    let mut result = format!(
+        "Auction State Diff:\n  {}: {}\n  {}: {}",
+        "Before".green(),
+        before.describe(),
+        "After".green(),
+        after.describe()
+    );
+    if before.address != after.address {
+        result.push_str(&format!(
+            "\n  {}: the snapshots are of different accounts",
+            "Warning".yellow()
+        ));
+    }
+    if after.slot < before.slot {
+        result.push_str(&format!(
+            "\n  {}: the second snapshot is from an earlier slot",
+            "Warning".yellow()
+        ));
+    }
+
+    let changes: Vec<(&str, String)> = before
+        .state
+        .fields()
+        .iter()
+        .zip(after.state.fields().iter())
+        .filter_map(|((name, a), (_, b))| format_change(a, b).map(|change| (*name, change)))
+        .collect();
+
+    if changes.is_empty() {
+        result.push_str(&format!("\n  {}: no fields changed", "Changes".green()));
+        return result;
+    }
+    result.push_str(&format!("\n  {}:", "Changes".green()));
+    for (name, change) in &changes {
+        result.push_str(&format!("\n    {}: {}", name.cyan(), change));
+    }
+    result.push_str(&format!(
+        "\n  {}: {} fields",
+        "Unchanged".green(),
+        before.state.fields().len() - changes.len()
+    ));
+    result
+}
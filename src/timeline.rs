@@ -0,0 +1,216 @@
+use crate::decoder::{self, DecodedInstruction, DecodedTransaction};
+use crate::rpc::Rpc;
+use anyhow::{Context, Result};
+use colored::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// Lifecycle stage of a Mayan instruction, by its decoded name
+fn stage_of(instruction_name: &str) -> Option<&'static str> {
+    let stage = match instruction_name {
+        "init_order" => "Init",
+        "bid" => "Bid",
+        "post_auction" => "Post Auction",
+        "close_auction" => "Close Auction",
+        "set_auction_winner" => "Set Winner",
+        "fulfill" => "Fulfill",
+        "settle" => "Settle",
+        "unlock" | "unlock_batch" => "Unlock",
+        "refund" => "Refund",
+        "cancel" => "Cancel",
+        "close" => "Close",
+        _ => return None,
+    };
+    Some(stage)
+}
+
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub signer: String,
+    pub failed: bool,
+    pub stage: String,
+    /// Names of every Mayan instruction in the transaction, including inner ones
+    pub instructions: Vec<String>,
+}
+
+fn collect_instruction_names(instructions: &[DecodedInstruction], names: &mut Vec<String>) {
+    for instruction in instructions {
+        if let (Some(program), Some(name)) = (instruction.program_name, &instruction.name) {
+            names.push(format!("{}: {}", program, name));
+        }
+        collect_instruction_names(&instruction.inner, names);
+    }
+}
+
+fn first_stage(instructions: &[DecodedInstruction]) -> Option<&'static str> {
+    instructions.iter().find_map(|instruction| {
+        instruction
+            .name
+            .as_deref()
+            .filter(|_| instruction.program_name.is_some())
+            .and_then(stage_of)
+            .or_else(|| first_stage(&instruction.inner))
+    })
+}
+
+pub fn classify_transaction(transaction: &DecodedTransaction) -> TimelineEntry {
+    let mut instructions = Vec::new();
+    collect_instruction_names(&transaction.instructions, &mut instructions);
+
+    TimelineEntry {
+        signature: transaction.signature.clone(),
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        signer: transaction.signer.clone(),
+        failed: transaction.err.is_some(),
+        stage: first_stage(&transaction.instructions)
+            .unwrap_or("Other")
+            .to_string(),
+        instructions,
+    }
+}
+
+/// Every transaction touching any of `addresses`, decoded, classified and in
+/// chronological order; a transaction touching several of them appears once
+pub fn collect_timeline(rpc: &Rpc, addresses: &[Pubkey]) -> Result<Vec<TimelineEntry>> {
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+
+    for address in addresses {
+        let signatures = rpc
+            .get_signatures_for_address(address)
+            .with_context(|| format!("Failed to get signatures for {}", address))?;
+
+        // Listed newest first; read oldest first so same-slot transactions of
+        // one address stay in execution order
+        for sig_info in signatures.into_iter().rev() {
+            if !seen.insert(sig_info.signature.clone()) {
+                continue;
+            }
+            let signature = Signature::from_str(&sig_info.signature)?;
+            let transaction = decoder::fetch_transaction(rpc, &signature)?;
+            let decoded = decoder::decode_transaction(&transaction)?;
+            entries.push(classify_transaction(&decoded));
+        }
+    }
+
+    entries.sort_by_key(|entry| (entry.slot, entry.block_time));
+    order_same_slot(&mut entries, rpc);
+    Ok(entries)
+}
+
+/// Order transactions of the same slot by their position in the block. A
+/// block that cannot be read keeps the order of the signature listings.
+fn order_same_slot(entries: &mut [TimelineEntry], rpc: &Rpc) {
+    for group in entries.chunk_by_mut(|a, b| a.slot == b.slot) {
+        if group.len() < 2 {
+            continue;
+        }
+        let block = match rpc.get_block_signatures(group[0].slot) {
+            Ok(block) => block,
+            Err(e) => {
+                eprintln!("Warning: {:#}", e);
+                continue;
+            }
+        };
+        group.sort_by_key(|entry| {
+            block
+                .iter()
+                .position(|signature| *signature == entry.signature)
+                .unwrap_or(usize::MAX)
+        });
+    }
+}
+
+pub fn format_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    match seconds {
+        0..=59 => format!("{}{}s", sign, seconds),
+        60..=3599 => format!("{}{}m {}s", sign, seconds / 60, seconds % 60),
+        _ => format!(
+            "{}{}h {}m {}s",
+            sign,
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        ),
+    }
+}
+
+fn format_time(block_time: Option<i64>) -> String {
+    block_time
+        .and_then(|time| chrono::DateTime::from_timestamp(time, 0))
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+pub fn format_timeline(entries: &[TimelineEntry]) -> String {
+    if entries.is_empty() {
+        return format!("{}: No transactions found", "Timeline".yellow());
+    }
+
+    let mut result = format!(
+        "{}: {} transactions found\n",
+        "Timeline".green(),
+        entries.len()
+    );
+
+    let start = entries.iter().find_map(|entry| entry.block_time);
+    // Start time of the current stage, to show how long each stage took to follow the last
+    let mut stage_start: Option<(&str, i64)> = None;
+
+    for entry in entries {
+        let elapsed = match (entry.block_time, stage_start) {
+            (Some(time), Some((stage, since))) if stage != entry.stage => {
+                format!(" (+{} after {})", format_duration(time - since), stage)
+            }
+            _ => String::new(),
+        };
+
+        result.push_str(&format!(
+            "\n{}{}\n  {}: {}\n  {}: {}\n  {}: {}{}\n  {}: {}\n  {}: {}",
+            entry.stage.cyan(),
+            elapsed,
+            "Signature".green(),
+            entry.signature,
+            "Slot".green(),
+            entry.slot,
+            "Time".green(),
+            format_time(entry.block_time),
+            match (entry.block_time, start) {
+                (Some(time), Some(start)) => format!(" (T+{})", format_duration(time - start)),
+                _ => String::new(),
+            },
+            "Signer".green(),
+            entry.signer,
+            "Status".green(),
+            if entry.failed {
+                "Failed".red()
+            } else {
+                "Success".green()
+            }
+        ));
+        if !entry.instructions.is_empty() {
+            result.push_str(&format!(
+                "\n  {}: {}",
+                "Instructions".green(),
+                entry.instructions.join(", ")
+            ));
+        }
+        result.push('\n');
+
+        if let Some(time) = entry.block_time {
+            if stage_start.is_none_or(|(stage, _)| stage != entry.stage) {
+                stage_start = Some((&entry.stage, time));
+            }
+        }
+    }
+
+    result
+}
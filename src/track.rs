@@ -0,0 +1,110 @@
+use colored::*;
+
+/// Where an order stands, as far as `track` is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    InProgress,
+    /// Cancelled; a cancelled Swift order goes on to be refunded
+    RefundPending,
+    Completed,
+    Refunded,
+    Failed,
+}
+
+impl Outcome {
+    pub fn is_final(self) -> bool {
+        !matches!(self, Outcome::InProgress | Outcome::RefundPending)
+    }
+
+    /// Process exit code for the outcome; 1 is left for errors and 4 is a timeout
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Outcome::Completed => 0,
+            Outcome::Refunded => 2,
+            Outcome::Failed => 3,
+            Outcome::InProgress | Outcome::RefundPending => TIMEOUT_EXIT_CODE,
+        }
+    }
+
+    pub fn label(self) -> ColoredString {
+        match self {
+            Outcome::InProgress => "In Progress".yellow(),
+            Outcome::RefundPending => "Refund Pending".yellow(),
+            Outcome::Completed => "Completed".green(),
+            Outcome::Refunded => "Refunded".yellow(),
+            Outcome::Failed => "Failed".red(),
+        }
+    }
+}
+
+pub const TIMEOUT_EXIT_CODE: i32 = 4;
+
+/// Classify an explorer status (`clientStatus` such as `COMPLETED`, or a
+/// detailed `status` such as `ORDER_SETTLED`). Fulfilled but not yet settled
+/// orders are still in progress, and cancelled ones wait for their refund.
+pub fn classify_status(status: &str) -> Outcome {
+    let status = status.to_ascii_uppercase();
+    if status.contains("REFUND") {
+        Outcome::Refunded
+    } else if status.contains("CANCEL") {
+        Outcome::RefundPending
+    } else if ["FAIL", "EXPIRED"].iter().any(|word| status.contains(word)) {
+        Outcome::Failed
+    } else if ["COMPLETED", "SETTLED", "UNLOCKED", "REDEEMED"]
+        .iter()
+        .any(|word| status.contains(word))
+    {
+        Outcome::Completed
+    } else {
+        Outcome::InProgress
+    }
+}
+
+/// Display status and outcome of an explorer order record. Both the detailed
+/// `status` and the summarized `clientStatus` are looked at, since either may
+/// be the first to reach a final value; `clientStatus` is checked first, so a
+/// cancelled order whose `clientStatus` is `REFUNDED` counts as refunded.
+pub fn record_status(record: &serde_json::Value) -> (String, Outcome) {
+    let status = |key: &str| {
+        record
+            .get(key)
+            .and_then(|value| value.as_str())
+            .filter(|status| !status.is_empty())
+    };
+    let statuses: Vec<&str> = ["status", "clientStatus"]
+        .iter()
+        .filter_map(|key| status(key))
+        .collect();
+
+    let outcomes: Vec<Outcome> = ["clientStatus", "status"]
+        .iter()
+        .filter_map(|key| status(key))
+        .map(classify_status)
+        .collect();
+    let outcome = outcomes
+        .iter()
+        .copied()
+        .find(|outcome| outcome.is_final())
+        .or_else(|| {
+            outcomes
+                .iter()
+                .copied()
+                .find(|o| *o == Outcome::RefundPending)
+        })
+        .unwrap_or(Outcome::InProgress);
+    let display = if statuses.is_empty() {
+        "Unknown".to_string()
+    } else {
+        statuses.join(" / ")
+    };
+    (display, outcome)
+}
+
+/// `[HH:MM:SS] message` progress line
+pub fn progress_line(message: &str) -> String {
+    format!(
+        "[{}] {}",
+        chrono::Local::now().format("%H:%M:%S").to_string().dimmed(),
+        message
+    )
+}
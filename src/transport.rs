@@ -0,0 +1,321 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_client::rpc_request::{RpcError, RpcRequest, RpcResponseErrorData};
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_rpc_client::http_sender::HttpSender;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How network traffic is handled, from the global `--record` and `--replay` flags
+#[derive(Clone)]
+pub enum TrafficMode {
+    Live,
+    /// Go to the network and save every request and response
+    Record(Arc<Recording>),
+    /// Serve saved responses without touching the network
+    Replay(Arc<Recording>),
+}
+
+impl TrafficMode {
+    pub fn new(record: Option<&str>, replay: Option<&str>) -> Result<Self> {
+        match (record, replay) {
+            (Some(_), Some(_)) => Err(anyhow::anyhow!(
+                "--record and --replay cannot be used together"
+            )),
+            (Some(dir), None) => {
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create recording directory {}", dir))?;
+                Ok(TrafficMode::Record(Arc::new(Recording::new(dir))))
+            }
+            (None, Some(dir)) => {
+                if !Path::new(dir).is_dir() {
+                    return Err(anyhow::anyhow!("Recording directory {} not found", dir));
+                }
+                Ok(TrafficMode::Replay(Arc::new(Recording::new(dir))))
+            }
+            (None, None) => Ok(TrafficMode::Live),
+        }
+    }
+}
+
+/// A directory of recorded exchanges, one JSON file per distinct request
+/// holding its responses in the order they were received. Replaying a request
+/// that was made several times (polling) returns the responses in turn, then
+/// keeps returning the last one.
+pub struct Recording {
+    dir: PathBuf,
+    write_lock: Mutex<()>,
+    replay_positions: Mutex<HashMap<String, usize>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecordedExchange {
+    request: Value,
+    responses: Vec<Value>,
+}
+
+impl Recording {
+    fn new(dir: &str) -> Self {
+        Recording {
+            dir: PathBuf::from(dir),
+            write_lock: Mutex::new(()),
+            replay_positions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// File name of a request: its kind and a hash of its canonical JSON
+    /// (object keys are sorted, so equal requests always hash the same)
+    fn file_name(kind: &str, request: &Value) -> String {
+        let hash = solana_sdk::hash::hash(request.to_string().as_bytes());
+        format!("{}-{}.json", kind, &hex::encode(hash.to_bytes())[..16])
+    }
+
+    fn append(&self, kind: &str, request: &Value, response: Value) -> Result<()> {
+        let _guard = self.write_lock.lock().unwrap();
+        let path = self.dir.join(Self::file_name(kind, request));
+        let mut exchange = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse recording {}", path.display()))?,
+            Err(_) => RecordedExchange {
+                request: request.clone(),
+                responses: Vec::new(),
+            },
+        };
+        exchange.responses.push(response);
+        let contents = serde_json::to_string_pretty(&exchange)?;
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Failed to write recording {}", path.display()))
+    }
+
+    fn next(&self, kind: &str, request: &Value) -> Result<Value> {
+        let name = Self::file_name(kind, request);
+        let path = self.dir.join(&name);
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|_| anyhow::anyhow!("No recorded response for {} {}", kind, request))?;
+        let exchange: RecordedExchange = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse recording {}", path.display()))?;
+
+        let mut positions = self.replay_positions.lock().unwrap();
+        let position = positions.entry(name).or_insert(0);
+        let response = exchange
+            .responses
+            .get(*position)
+            .or(exchange.responses.last())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Recording {} has no responses", path.display()))?;
+        *position += 1;
+        Ok(response)
+    }
+}
+
+fn rpc_request_json(request: RpcRequest, params: &Value) -> Value {
+    json!({ "method": request.to_string(), "params": params })
+}
+
+/// JSON-RPC errors keep their code so they replay as the same error; transport
+/// errors are kept as a message
+fn encode_rpc_result(result: &ClientResult<Value>) -> Value {
+    match result {
+        Ok(value) => json!({ "result": value }),
+        Err(e) => match &e.kind {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, message, .. }) => {
+                json!({ "error": { "code": code, "message": message } })
+            }
+            _ => json!({ "error": { "message": e.to_string() } }),
+        },
+    }
+}
+
+fn decode_rpc_error(response: &Value) -> ClientError {
+    let error = response.get("error").cloned().unwrap_or_default();
+    let message = error
+        .get("message")
+        .and_then(|message| message.as_str())
+        .unwrap_or("Recorded request failed")
+        .to_string();
+    let kind = match error.get("code").and_then(|code| code.as_i64()) {
+        Some(code) => ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            code,
+            message,
+            data: RpcResponseErrorData::Empty,
+        }),
+        None => ClientErrorKind::Custom(message),
+    };
+    ClientError::from(kind)
+}
+
+/// Forwards to an HTTP endpoint and records every exchange
+struct RecordingSender {
+    inner: HttpSender,
+    recording: Arc<Recording>,
+}
+
+#[async_trait]
+impl RpcSender for RecordingSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let request_json = rpc_request_json(request, &params);
+        let result = self.inner.send(request, params).await;
+        self.recording
+            .append("rpc", &request_json, encode_rpc_result(&result))
+            .map_err(|e| ClientError::from(ClientErrorKind::Custom(e.to_string())))?;
+        result
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+/// Serves recorded responses; requests are matched on method and parameters,
+/// whatever the endpoint they were recorded from
+struct ReplaySender {
+    url: String,
+    recording: Arc<Recording>,
+}
+
+#[async_trait]
+impl RpcSender for ReplaySender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let response = self
+            .recording
+            .next("rpc", &rpc_request_json(request, &params))
+            .map_err(|e| ClientError::from(ClientErrorKind::Custom(e.to_string())))?;
+        match response.get("result") {
+            Some(result) => Ok(result.clone()),
+            None => Err(decode_rpc_error(&response)),
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        self.url.clone()
+    }
+}
+
+pub fn rpc_client(
+    url: &str,
+    timeout: Duration,
+    commitment: CommitmentConfig,
+    mode: &TrafficMode,
+) -> RpcClient {
+    let config = RpcClientConfig::with_commitment(commitment);
+    match mode {
+        TrafficMode::Live => RpcClient::new_with_timeout_and_commitment(url, timeout, commitment),
+        TrafficMode::Record(recording) => RpcClient::new_sender(
+            RecordingSender {
+                inner: HttpSender::new_with_timeout(url, timeout),
+                recording: recording.clone(),
+            },
+            config,
+        ),
+        TrafficMode::Replay(recording) => RpcClient::new_sender(
+            ReplaySender {
+                url: url.to_string(),
+                recording: recording.clone(),
+            },
+            config,
+        ),
+    }
+}
+
+/// Response of an HTTP GET to one of the REST APIs (explorer, Wormhole)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_str(&self.body)?)
+    }
+}
+
+/// Client for the REST APIs, so their traffic can be recorded and replayed
+#[async_trait]
+pub trait HttpApi: Send + Sync {
+    async fn get(&self, url: &str) -> Result<HttpResponse>;
+}
+
+pub struct LiveHttp {
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl HttpApi for LiveHttp {
+    async fn get(&self, url: &str) -> Result<HttpResponse> {
+        let response = self.client.get(url).send().await?;
+        let status = response.status().as_u16();
+        let body = response.text().await?;
+        Ok(HttpResponse { status, body })
+    }
+}
+
+struct RecordingHttp {
+    inner: LiveHttp,
+    recording: Arc<Recording>,
+}
+
+#[async_trait]
+impl HttpApi for RecordingHttp {
+    async fn get(&self, url: &str) -> Result<HttpResponse> {
+        let result = self.inner.get(url).await;
+        let response = match &result {
+            Ok(response) => serde_json::to_value(response)?,
+            Err(e) => json!({ "error": e.to_string() }),
+        };
+        self.recording
+            .append("http", &json!({ "url": url }), response)?;
+        result
+    }
+}
+
+struct ReplayHttp {
+    recording: Arc<Recording>,
+}
+
+#[async_trait]
+impl HttpApi for ReplayHttp {
+    async fn get(&self, url: &str) -> Result<HttpResponse> {
+        let response = self.recording.next("http", &json!({ "url": url }))?;
+        if let Some(error) = response.get("error").and_then(|error| error.as_str()) {
+            return Err(anyhow::anyhow!("{}", error));
+        }
+        Ok(serde_json::from_value(response)?)
+    }
+}
+
+pub fn http_api(mode: &TrafficMode) -> Arc<dyn HttpApi> {
+    let live = LiveHttp {
+        client: reqwest::Client::new(),
+    };
+    match mode {
+        TrafficMode::Live => Arc::new(live),
+        TrafficMode::Record(recording) => Arc::new(RecordingHttp {
+            inner: live,
+            recording: recording.clone(),
+        }),
+        TrafficMode::Replay(recording) => Arc::new(ReplayHttp {
+            recording: recording.clone(),
+        }),
+    }
+}
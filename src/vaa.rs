@@ -0,0 +1,503 @@
+use crate::transport::HttpApi;
+use anyhow::{Context, Result};
+use base64::Engine;
+use colored::*;
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+
+/// Size of the fixed part of a VAA header (version, guardian set index, signature count)
+const HEADER_LEN: usize = 6;
+/// Guardian index (1 byte) followed by a 65-byte secp256k1 signature
+const SIGNATURE_LEN: usize = 66;
+/// timestamp + nonce + emitter chain + emitter address + sequence + consistency level
+const BODY_HEADER_LEN: usize = 4 + 4 + 2 + 32 + 8 + 1;
+
+#[derive(Debug, Clone)]
+pub struct GuardianSignature {
+    pub guardian_index: u8,
+    pub signature: [u8; 65],
+}
+
+#[derive(Debug, Clone)]
+pub struct Vaa {
+    pub version: u8,
+    pub guardian_set_index: u32,
+    pub signatures: Vec<GuardianSignature>,
+    pub timestamp: u32,
+    pub nonce: u32,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub consistency_level: u8,
+    pub payload: Vec<u8>,
+    /// keccak256(keccak256(body)), the value guardians sign
+    pub digest: [u8; 32],
+}
+
+/// A Mayan payload decoded into named fields, in wire order
+#[derive(Debug, Clone)]
+pub struct DecodedPayload {
+    pub kind: &'static str,
+    pub fields: Vec<(String, String)>,
+}
+
+/// Big-endian cursor over a byte slice, matching Wormhole's wire encoding
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.pos + len > self.data.len() {
+            return Err(anyhow::anyhow!(
+                "Unexpected end of data: need {} bytes at offset {}, have {}",
+                len,
+                self.pos,
+                self.data.len() - self.pos
+            ));
+        }
+        let slice = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into()?))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn bytes32(&mut self) -> Result<[u8; 32]> {
+        Ok(self.take(32)?.try_into()?)
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        let slice = &self.data[self.pos..];
+        self.pos = self.data.len();
+        slice
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SignedVaaResponse {
+    #[serde(rename = "vaaBytes")]
+    vaa_bytes: String,
+}
+
+/// Fetch a signed VAA from a guardian or Wormholescan-style API
+/// (`GET {api_url}/v1/signed_vaa/{chain}/{emitter}/{sequence}`).
+/// Returns `None` when the API does not have the VAA yet, i.e. it is not signed.
+pub async fn fetch_signed_vaa(
+    http: &dyn HttpApi,
+    api_url: &str,
+    emitter_chain: u16,
+    emitter_address: &[u8; 32],
+    sequence: u64,
+) -> Result<Option<Vec<u8>>> {
+    let url = format!(
+        "{}/v1/signed_vaa/{}/{}/{}",
+        api_url.trim_end_matches('/'),
+        emitter_chain,
+        hex::encode(emitter_address),
+        sequence
+    );
+
+    let response = http
+        .get(&url)
+        .await
+        .context("Failed to send request to Wormhole API")?;
+
+    if response.status == 404 {
+        return Ok(None);
+    }
+    if !response.is_success() {
+        return Err(anyhow::anyhow!(
+            "Wormhole API request failed with status: {}",
+            response.status
+        ));
+    }
+
+    let signed: SignedVaaResponse = response
+        .json()
+        .context("Failed to parse Wormhole API response")?;
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&signed.vaa_bytes)
+        .context("Failed to decode VAA bytes from Wormhole API")?;
+
+    Ok(Some(bytes))
+}
+
+/// Read VAA bytes from a file path or an inline base64/hex string.
+/// Files may contain raw bytes or base64/hex text.
+pub fn read_vaa_input(input: &str, format: &str) -> Result<Vec<u8>> {
+    let path = Path::new(input);
+    if path.is_file() {
+        let contents = std::fs::read(path).context("Failed to read VAA file")?;
+        return match std::str::from_utf8(&contents) {
+            Ok(text) => decode_vaa_string(text.trim(), format),
+            Err(_) => Ok(contents),
+        };
+    }
+
+    decode_vaa_string(input.trim(), format)
+}
+
+fn decode_vaa_string(input: &str, format: &str) -> Result<Vec<u8>> {
+    match format.to_lowercase().as_str() {
+        "hex" => {
+            let hex_str = input.strip_prefix("0x").unwrap_or(input);
+            hex::decode(hex_str).context("Failed to decode hex string")
+        }
+        "base64" => base64::engine::general_purpose::STANDARD
+            .decode(input)
+            .context("Failed to decode base64 string"),
+        "auto" => {
+            let hex_str = input.strip_prefix("0x").unwrap_or(input);
+            if hex_str.len().is_multiple_of(2) && hex_str.chars().all(|c| c.is_ascii_hexdigit()) {
+                hex::decode(hex_str).context("Failed to decode hex string")
+            } else {
+                base64::engine::general_purpose::STANDARD
+                    .decode(input)
+                    .context("Failed to decode input as hex or base64")
+            }
+        }
+        _ => Err(anyhow::anyhow!(
+            "Invalid format '{}'. Valid formats are: auto, base64, hex",
+            format
+        )),
+    }
+}
+
+pub fn parse_vaa(data: &[u8]) -> Result<Vaa> {
+    if data.len() < HEADER_LEN {
+        return Err(anyhow::anyhow!("VAA is too short: {} bytes", data.len()));
+    }
+
+    let mut reader = Reader::new(data);
+    let version = reader.u8()?;
+    if version != 1 {
+        return Err(anyhow::anyhow!("Unsupported VAA version: {}", version));
+    }
+    let guardian_set_index = reader.u32()?;
+    let num_signatures = reader.u8()? as usize;
+
+    let mut signatures = Vec::with_capacity(num_signatures);
+    for _ in 0..num_signatures {
+        let raw = reader
+            .take(SIGNATURE_LEN)
+            .context("Failed to read guardian signature")?;
+        signatures.push(GuardianSignature {
+            guardian_index: raw[0],
+            signature: raw[1..].try_into()?,
+        });
+    }
+
+    let body = &data[reader.pos..];
+    if body.len() < BODY_HEADER_LEN {
+        return Err(anyhow::anyhow!(
+            "VAA body is too short: {} bytes",
+            body.len()
+        ));
+    }
+    let digest: [u8; 32] = Keccak256::digest(Keccak256::digest(body)).into();
+
+    Ok(Vaa {
+        version,
+        guardian_set_index,
+        signatures,
+        timestamp: reader.u32()?,
+        nonce: reader.u32()?,
+        emitter_chain: reader.u16()?,
+        emitter_address: reader.bytes32()?,
+        sequence: reader.u64()?,
+        consistency_level: reader.u8()?,
+        payload: reader.rest().to_vec(),
+        digest,
+    })
+}
+
+/// Wormhole chain IDs for the chains Mayan is deployed on
+pub fn chain_name(chain_id: u16) -> &'static str {
+    match chain_id {
+        1 => "Solana",
+        2 => "Ethereum",
+        4 => "BSC",
+        5 => "Polygon",
+        6 => "Avalanche",
+        23 => "Arbitrum",
+        24 => "Optimism",
+        30 => "Base",
+        _ => "Unknown",
+    }
+}
+
+pub fn format_address(chain_id: u16, address: &[u8; 32]) -> String {
+    if chain_id == 1 {
+        Pubkey::new_from_array(*address).to_string()
+    } else if address[..12].iter().all(|b| *b == 0) {
+        // EVM addresses are left-padded to 32 bytes
+        format!("0x{}", hex::encode(&address[12..]))
+    } else {
+        format!("0x{}", hex::encode(address))
+    }
+}
+
+/// Decode known Mayan Swift and MCTP payloads. Both protocols start with a
+/// one-byte action, so the action and the exact payload length select the layout.
+pub fn decode_mayan_payload(payload: &[u8]) -> Option<DecodedPayload> {
+    let action = *payload.first()?;
+    let result = match (action, payload.len()) {
+        (1, 75) => decode_swift_fulfill(payload),
+        (2, 99) => decode_swift_unlock(payload),
+        (3, 115) => decode_swift_refund(payload),
+        (4, len) if len >= 3 && (len - 3).is_multiple_of(98) => decode_swift_batch_unlock(payload),
+        (1, 134) => decode_mctp_bridge_with_fee(payload),
+        (2, 102) => decode_mctp_bridge_with_locked_fee(payload),
+        (4, 54) => decode_mctp_unlock_fee(payload),
+        _ => return None,
+    };
+    result.ok()
+}
+
+fn field(name: &str, value: impl ToString) -> (String, String) {
+    (name.to_string(), value.to_string())
+}
+
+fn decode_swift_fulfill(payload: &[u8]) -> Result<DecodedPayload> {
+    let mut r = Reader::new(payload);
+    let action = r.u8()?;
+    Ok(DecodedPayload {
+        kind: "Swift Fulfill",
+        fields: vec![
+            field("Action", action),
+            field("Order Hash", hex::encode(r.bytes32()?)),
+            field("Driver", hex::encode(r.bytes32()?)),
+            field("Promised Amount", r.u64()?),
+            field("Penalty Period", r.u16()?),
+        ],
+    })
+}
+
+fn decode_swift_unlock(payload: &[u8]) -> Result<DecodedPayload> {
+    let mut r = Reader::new(payload);
+    let action = r.u8()?;
+    let order_hash = r.bytes32()?;
+    let src_chain = r.u16()?;
+    Ok(DecodedPayload {
+        kind: "Swift Unlock",
+        fields: vec![
+            field("Action", action),
+            field("Order Hash", hex::encode(order_hash)),
+            field(
+                "Source Chain",
+                format!("{} ({})", src_chain, chain_name(src_chain)),
+            ),
+            field("Token In", format_address(src_chain, &r.bytes32()?)),
+            field("Unlock Receiver", format_address(src_chain, &r.bytes32()?)),
+        ],
+    })
+}
+
+fn decode_swift_refund(payload: &[u8]) -> Result<DecodedPayload> {
+    let mut r = Reader::new(payload);
+    let action = r.u8()?;
+    let order_hash = r.bytes32()?;
+    let src_chain = r.u16()?;
+    Ok(DecodedPayload {
+        kind: "Swift Refund",
+        fields: vec![
+            field("Action", action),
+            field("Order Hash", hex::encode(order_hash)),
+            field(
+                "Source Chain",
+                format!("{} ({})", src_chain, chain_name(src_chain)),
+            ),
+            field("Token In", format_address(src_chain, &r.bytes32()?)),
+            field("Recipient", format_address(src_chain, &r.bytes32()?)),
+            field("Cancel Fee", r.u64()?),
+            field("Refund Fee", r.u64()?),
+        ],
+    })
+}
+
+fn decode_swift_batch_unlock(payload: &[u8]) -> Result<DecodedPayload> {
+    let mut r = Reader::new(payload);
+    let action = r.u8()?;
+    let count = r.u16()?;
+    let mut fields = vec![field("Action", action), field("Count", count)];
+    for i in 0..count {
+        let order_hash = r.bytes32()?;
+        let src_chain = r.u16()?;
+        let token_in = r.bytes32()?;
+        let receiver = r.bytes32()?;
+        fields.push(field(
+            &format!("Order {}", i + 1),
+            format!(
+                "{} (source chain {}, token in {}, receiver {})",
+                hex::encode(order_hash),
+                src_chain,
+                format_address(src_chain, &token_in),
+                format_address(src_chain, &receiver)
+            ),
+        ));
+    }
+    if !r.rest().is_empty() {
+        return Err(anyhow::anyhow!("Trailing bytes in batch unlock payload"));
+    }
+    Ok(DecodedPayload {
+        kind: "Swift Batch Unlock",
+        fields,
+    })
+}
+
+fn decode_mctp_bridge_with_fee(payload: &[u8]) -> Result<DecodedPayload> {
+    let mut r = Reader::new(payload);
+    Ok(DecodedPayload {
+        kind: "MCTP Bridge With Fee",
+        fields: vec![
+            field("Action", r.u8()?),
+            field("Payload Type", r.u8()?),
+            field("CCTP Nonce", r.u64()?),
+            field("CCTP Domain", r.u32()?),
+            field("Destination Address", hex::encode(r.bytes32()?)),
+            field("Gas Drop", r.u64()?),
+            field("Redeem Fee", r.u64()?),
+            field("Burn Amount", r.u64()?),
+            field("Burn Token", hex::encode(r.bytes32()?)),
+            field("Custom Payload", hex::encode(r.bytes32()?)),
+        ],
+    })
+}
+
+fn decode_mctp_bridge_with_locked_fee(payload: &[u8]) -> Result<DecodedPayload> {
+    let mut r = Reader::new(payload);
+    Ok(DecodedPayload {
+        kind: "MCTP Bridge With Locked Fee",
+        fields: vec![
+            field("Action", r.u8()?),
+            field("Payload Type", r.u8()?),
+            field("CCTP Nonce", r.u64()?),
+            field("CCTP Domain", r.u32()?),
+            field("Destination Address", hex::encode(r.bytes32()?)),
+            field("Gas Drop", r.u64()?),
+            field("Fee Redeem", r.u64()?),
+            field("Burn Amount", r.u64()?),
+            field("Burn Token", hex::encode(r.bytes32()?)),
+        ],
+    })
+}
+
+fn decode_mctp_unlock_fee(payload: &[u8]) -> Result<DecodedPayload> {
+    let mut r = Reader::new(payload);
+    Ok(DecodedPayload {
+        kind: "MCTP Unlock Fee",
+        fields: vec![
+            field("Action", r.u8()?),
+            field("Payload Type", r.u8()?),
+            field("CCTP Nonce", r.u64()?),
+            field("CCTP Domain", r.u32()?),
+            field("Unlocker Address", hex::encode(r.bytes32()?)),
+            field("Gas Drop", r.u64()?),
+        ],
+    })
+}
+
+pub fn format_vaa(vaa: &Vaa) -> String {
+    let mut result = format!(
+        "VAA Details:
+  {}: {}
+  {}: {}
+  {}: {}",
+        "Version".green(),
+        vaa.version,
+        "Guardian Set Index".green(),
+        vaa.guardian_set_index,
+        "Signatures".green(),
+        vaa.signatures.len()
+    );
+
+    for sig in &vaa.signatures {
+        result.push_str(&format!(
+            "\n    [{}] {}",
+            sig.guardian_index,
+            hex::encode(sig.signature)
+        ));
+    }
+
+    let timestamp = chrono::DateTime::from_timestamp(vaa.timestamp as i64, 0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default();
+    let emitter = if vaa.emitter_chain == 1 {
+        format!(
+            "{} ({})",
+            hex::encode(vaa.emitter_address),
+            Pubkey::new_from_array(vaa.emitter_address)
+        )
+    } else {
+        hex::encode(vaa.emitter_address)
+    };
+
+    result.push_str(&format!(
+        "\n  {}: {} ({})
+  {}: {}
+  {}: {} ({})
+  {}: {}
+  {}: {}
+  {}: {}
+  {}: {}
+  {}: {}",
+        "Timestamp".green(),
+        vaa.timestamp,
+        timestamp,
+        "Nonce".green(),
+        vaa.nonce,
+        "Emitter Chain".green(),
+        vaa.emitter_chain,
+        chain_name(vaa.emitter_chain),
+        "Emitter Address".green(),
+        emitter,
+        "Sequence".green(),
+        vaa.sequence,
+        "Consistency Level".green(),
+        vaa.consistency_level,
+        "Digest".green(),
+        hex::encode(vaa.digest),
+        "Payload".green(),
+        hex::encode(&vaa.payload)
+    ));
+
+    match decode_mayan_payload(&vaa.payload) {
+        Some(decoded) => {
+            result.push_str(&format!("\n\n{} ({}):", "Payload".cyan(), decoded.kind));
+            for (name, value) in &decoded.fields {
+                result.push_str(&format!("\n  {}: {}", name.green(), value));
+            }
+        }
+        None => {
+            result.push_str(&format!(
+                "\n\n{}: Unrecognized payload type",
+                "Payload".yellow()
+            ));
+        }
+    }
+
+    result
+}
@@ -0,0 +1,156 @@
+//! Runs `get-auction-vaa` against a local mock RPC serving the auction state and
+//! a mock Wormhole API, for each state the auction result message can be in.
+
+mod common;
+
+use base64::Engine;
+use common::{mock_endpoint, recorded_auction_state, rpc_endpoint, Request, AUCTION_STATE};
+use serde_json::{json, Value};
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+
+const SEQUENCE: u64 = 42;
+
+/// The recorded auction state with its `seq_msg` (the last field) replaced
+fn auction_state(seq_msg: u64) -> Value {
+    let mut account = recorded_auction_state();
+    let engine = base64::engine::general_purpose::STANDARD;
+    let mut data = engine
+        .decode(account["result"]["value"]["data"][0].as_str().unwrap())
+        .unwrap();
+    let len = data.len();
+    data[len - 8..].copy_from_slice(&seq_msg.to_le_bytes());
+    account["result"]["value"]["data"][0] = engine.encode(data).into();
+    account
+}
+
+/// A VAA with one guardian signature, emitted by `emitter` at `sequence`,
+/// carrying a Swift fulfill payload
+fn signed_vaa(emitter: [u8; 32], sequence: u64) -> Vec<u8> {
+    let mut vaa = vec![1];
+    vaa.extend(4u32.to_be_bytes());
+    vaa.push(1);
+    vaa.push(0);
+    vaa.extend([0xab; 65]);
+    vaa.extend(1_748_670_600u32.to_be_bytes());
+    vaa.extend(0u32.to_be_bytes());
+    vaa.extend(1u16.to_be_bytes());
+    vaa.extend(emitter);
+    vaa.extend(sequence.to_be_bytes());
+    vaa.push(1);
+    vaa.push(1);
+    vaa.extend([0x11; 32]);
+    vaa.extend([0x22; 32]);
+    vaa.extend(644_921_303u64.to_be_bytes());
+    vaa.extend(0u16.to_be_bytes());
+    vaa
+}
+
+/// Emitter address of a `/v1/signed_vaa/{chain}/{emitter}/{sequence}` request
+fn requested_emitter(request: &Request) -> [u8; 32] {
+    let emitter = request.path.split('/').nth(4).unwrap();
+    hex::decode(emitter).unwrap().try_into().unwrap()
+}
+
+fn get_auction_vaa(seq_msg: u64, wormhole_status: u16) -> (Output, Vec<Request>) {
+    let rpc = rpc_endpoint(Arc::new(Mutex::new(Vec::new())), auction_state(seq_msg));
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let wormhole = mock_endpoint(requests.clone(), move |request| match wormhole_status {
+        200 => {
+            let vaa = signed_vaa(requested_emitter(request), SEQUENCE);
+            let body = json!({
+                "vaaBytes": base64::engine::general_purpose::STANDARD.encode(vaa)
+            });
+            (200, body.to_string())
+        }
+        status => (
+            status,
+            json!({ "code": 5, "message": "not found" }).to_string(),
+        ),
+    });
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mayan-cli"))
+        .args(["get-auction-vaa", AUCTION_STATE])
+        .args(["--rpc-url", &rpc])
+        .args(["--wormhole-api", &wormhole])
+        .env("NO_COLOR", "1")
+        .env_remove("SOLANA_RPC_URL")
+        .env_remove("WORMHOLE_API_URL")
+        .output()
+        .expect("failed to run mayan-cli");
+    let requests = requests.lock().unwrap().clone();
+    (output, requests)
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "mayan-cli failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn signed_vaa_is_fetched_and_parsed() {
+    let (output, requests) = get_auction_vaa(SEQUENCE, 200);
+    let stdout = stdout(&output);
+
+    assert_eq!(requests.len(), 1);
+    let path: Vec<&str> = requests[0].path.split('/').collect();
+    assert_eq!(path[..4], ["", "v1", "signed_vaa", "1"]);
+    assert_eq!(path[4].len(), 64);
+    assert_eq!(path[5], SEQUENCE.to_string());
+
+    assert!(stdout.contains("Sequence: 42\n"), "{}", stdout);
+    assert!(stdout.contains("VAA Details:"), "{}", stdout);
+    assert!(stdout.contains("Guardian Set Index: 4"), "{}", stdout);
+    assert!(stdout.contains("Signatures: 1"), "{}", stdout);
+    assert!(stdout.contains("Emitter Chain: 1 (Solana)"), "{}", stdout);
+    assert!(
+        stdout.contains(&format!("Emitter Address: {}", path[4])),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn unsigned_vaa_is_reported() {
+    let (output, requests) = get_auction_vaa(SEQUENCE, 404);
+    let stdout = stdout(&output);
+
+    assert_eq!(requests.len(), 1);
+    assert!(
+        stdout.contains("VAA: Message is not signed by the guardians yet"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("VAA Details:"), "{}", stdout);
+}
+
+#[test]
+fn unposted_result_skips_wormhole() {
+    let (output, requests) = get_auction_vaa(0, 200);
+    let stdout = stdout(&output);
+
+    assert!(requests.is_empty(), "{:?}", requests);
+    assert!(stdout.contains("Sequence: 0\n"), "{}", stdout);
+    assert!(
+        stdout.contains("VAA: Auction result has not been posted to Wormhole yet"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn wormhole_api_error_fails() {
+    let (output, requests) = get_auction_vaa(SEQUENCE, 500);
+
+    assert_eq!(requests.len(), 1);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("failed with status: 500"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
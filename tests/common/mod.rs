@@ -0,0 +1,136 @@
+//! Local mock HTTP endpoints for the tests that run commands against live
+//! (not replayed) RPC and API traffic.
+
+#![allow(dead_code)]
+
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+pub const AUCTION_STATE: &str = "6p7fUeppNLatf5TkmMA4ybpSJBejMnGSRknhPfEBNSF3";
+
+pub fn fixture_dir(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+/// A request received by a mock endpoint
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub path: String,
+    pub body: String,
+}
+
+impl Request {
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap()
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> Request {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let path = request_line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line == "\r\n" || line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    Request {
+        path,
+        body: String::from_utf8(body).unwrap(),
+    }
+}
+
+/// Serves every request with the status and body `respond` returns for it,
+/// keeping the requests it received. Returns the endpoint URL.
+pub fn mock_endpoint(
+    requests: Arc<Mutex<Vec<Request>>>,
+    respond: impl Fn(&Request) -> (u16, String) + Send + 'static,
+) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let request = read_request(&mut stream);
+            let (status, body) = respond(&request);
+            requests.lock().unwrap().push(request);
+            write!(
+                stream,
+                "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                if status == 200 { "OK" } else { "Error" },
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+    url
+}
+
+/// Accepts connections and never answers them
+pub fn slow_endpoint() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = stream.unwrap();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_secs(60));
+                drop(stream);
+            });
+        }
+    });
+    url
+}
+
+/// The recorded `getAccountInfo` response of the `swift-auction` auction state
+pub fn recorded_auction_state() -> Value {
+    let recording: Value = serde_json::from_str(
+        &std::fs::read_to_string(fixture_dir("swift-auction").join("rpc-ee5c08451f0db464.json"))
+            .unwrap(),
+    )
+    .unwrap();
+    recording["responses"][0].clone()
+}
+
+/// A JSON-RPC endpoint answering `getAccountInfo` with `account` (a
+/// `{"result": ...}` response) and anything else with an error
+pub fn rpc_endpoint(requests: Arc<Mutex<Vec<Request>>>, account: Value) -> String {
+    mock_endpoint(requests, move |request| {
+        let request = request.json();
+        let mut response = match request["method"].as_str() {
+            Some("getAccountInfo") => account.clone(),
+            _ => serde_json::json!({
+                "error": { "code": -32601, "message": "Method not found" }
+            }),
+        };
+        response["jsonrpc"] = "2.0".into();
+        response["id"] = request["id"].clone();
+        (200, response.to_string())
+    })
+}
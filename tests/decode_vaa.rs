@@ -0,0 +1,129 @@
+//! Runs `decode-vaa` on truncated and malformed VAAs, checking that each part
+//! of the parser reports what is missing instead of misreading the bytes.
+
+use std::process::{Command, Output};
+
+fn decode_vaa(bytes: &[u8]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mayan-cli"))
+        .args(["decode-vaa", &hex::encode(bytes), "--format", "hex"])
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run mayan-cli")
+}
+
+fn error(bytes: &[u8]) -> String {
+    let output = decode_vaa(bytes);
+    assert!(!output.status.success(), "decoded {}", hex::encode(bytes));
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+fn decoded(bytes: &[u8]) -> String {
+    let output = decode_vaa(bytes);
+    assert!(
+        output.status.success(),
+        "mayan-cli failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Version 1 header with `signatures` announced but only `present` included
+fn header(signatures: u8, present: u8) -> Vec<u8> {
+    let mut vaa = vec![1];
+    vaa.extend(4u32.to_be_bytes());
+    vaa.push(signatures);
+    for index in 0..present {
+        vaa.push(index);
+        vaa.extend([0xab; 65]);
+    }
+    vaa
+}
+
+/// Timestamp, nonce, emitter chain and address, sequence and consistency level
+fn body_header() -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend(1_748_670_600u32.to_be_bytes());
+    body.extend(0u32.to_be_bytes());
+    body.extend(1u16.to_be_bytes());
+    body.extend([0x33; 32]);
+    body.extend(42u64.to_be_bytes());
+    body.push(1);
+    body
+}
+
+/// Action 1 followed by `len - 1` bytes: a Swift fulfill when `len` is 75
+fn fulfill_payload(len: usize) -> Vec<u8> {
+    let mut payload = vec![1];
+    payload.resize(len, 0);
+    payload
+}
+
+#[test]
+fn truncated_header_fails() {
+    assert!(error(&[1, 0, 0]).contains("VAA is too short: 3 bytes"));
+    assert!(error(&[]).contains("VAA is too short: 0 bytes"));
+}
+
+#[test]
+fn unsupported_version_fails() {
+    let mut vaa = header(0, 0);
+    vaa[0] = 2;
+    vaa.extend(body_header());
+    assert!(error(&vaa).contains("Unsupported VAA version: 2"));
+}
+
+#[test]
+fn missing_signature_fails() {
+    let mut vaa = header(2, 1);
+    vaa.extend(body_header());
+    // Fewer bytes left than the announced second signature needs
+    vaa.truncate(6 + 66 + 60);
+    assert!(error(&vaa).contains("Failed to read guardian signature"));
+
+    let mut vaa = header(1, 0);
+    vaa.extend([0xab; 10]);
+    assert!(error(&vaa).contains("Failed to read guardian signature"));
+}
+
+#[test]
+fn truncated_body_fails() {
+    let mut vaa = header(1, 1);
+    vaa.extend(&body_header()[..50]);
+    assert!(error(&vaa).contains("VAA body is too short: 50 bytes"));
+}
+
+#[test]
+fn body_without_payload_decodes() {
+    let mut vaa = header(1, 1);
+    vaa.extend(body_header());
+    let output = decoded(&vaa);
+    assert!(output.contains("Signatures: 1"), "{}", output);
+    assert!(output.contains("Sequence: 42"), "{}", output);
+    assert!(
+        output.contains("Payload: Unrecognized payload type"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn truncated_payload_is_not_decoded() {
+    for len in [1, 74, 76] {
+        let mut vaa = header(1, 1);
+        vaa.extend(body_header());
+        vaa.extend(fulfill_payload(len));
+        let output = decoded(&vaa);
+        assert!(
+            output.contains("Payload: Unrecognized payload type"),
+            "{} byte payload: {}",
+            len,
+            output
+        );
+        assert!(!output.contains("Swift Fulfill"), "{}", output);
+    }
+
+    let mut vaa = header(1, 1);
+    vaa.extend(body_header());
+    vaa.extend(fulfill_payload(75));
+    assert!(decoded(&vaa).contains("Payload (Swift Fulfill):"));
+}
@@ -0,0 +1,150 @@
+//! Runs commands against recorded traffic (`--replay`) and compares their
+//! output with the expected output stored next to the recording.
+//!
+//! To add a case, record a command into a new directory under
+//! `tests/fixtures/` with `--record <dir>` and save its output there.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const AUCTION_STATE: &str = "6p7fUeppNLatf5TkmMA4ybpSJBejMnGSRknhPfEBNSF3";
+const ORDER_ID: &str = "SWIFT_0x8c15dfce1d427d2f209eb02b2c0284e8fb8c13f685111503607d151a3a5ec663";
+
+fn fixture_dir(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn replay(fixture: &str, args: &[&str]) -> String {
+    replay_dir(&fixture_dir(fixture), args)
+}
+
+fn replay_dir(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_mayan-cli"))
+        .arg("--replay")
+        .arg(dir)
+        .args(args)
+        .env("NO_COLOR", "1")
+        .env_remove("SOLANA_RPC_URL")
+        .output()
+        .expect("failed to run mayan-cli");
+    assert!(
+        output.status.success(),
+        "mayan-cli {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("output is not UTF-8")
+}
+
+fn expected(fixture: &str, file: &str) -> String {
+    std::fs::read_to_string(fixture_dir(fixture).join(file)).expect("missing expected output")
+}
+
+#[test]
+fn auction_state_by_address() {
+    assert_eq!(
+        replay("swift-auction", &["get-auction-state", AUCTION_STATE]),
+        expected("swift-auction", "get-auction-state.out")
+    );
+}
+
+#[test]
+fn auction_state_by_order_id() {
+    assert_eq!(
+        replay("swift-auction", &["get-auction-state", ORDER_ID]),
+        expected("swift-auction", "get-auction-state.out")
+    );
+}
+
+#[test]
+fn decode_account_with_idl() {
+    let idl = fixture_dir("swift-auction").join("swift_auction.idl.json");
+    assert_eq!(
+        replay(
+            "swift-auction",
+            &[
+                "--idl",
+                idl.to_str().unwrap(),
+                "decode-account",
+                AUCTION_STATE
+            ]
+        ),
+        expected("swift-auction", "decode-account-idl.out")
+    );
+}
+
+#[test]
+fn bid_history_by_address() {
+    assert_eq!(
+        replay("swift-auction", &["get-bids", AUCTION_STATE]),
+        expected("swift-auction", "get-bids.out")
+    );
+}
+
+#[test]
+fn bid_history_by_order_id() {
+    assert_eq!(
+        replay("swift-auction", &["get-bids", ORDER_ID]),
+        expected("swift-auction", "get-bids.out")
+    );
+}
+
+/// A closed auction is reconciled against its highest bid, so `--strict`
+/// passes
+#[test]
+fn bid_history_of_closed_auction() {
+    let dir = std::env::temp_dir().join(format!("mayan-closed-auction-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for entry in std::fs::read_dir(fixture_dir("swift-auction")).unwrap() {
+        let path = entry.unwrap().path();
+        std::fs::copy(&path, dir.join(path.file_name().unwrap())).unwrap();
+    }
+    // The getAccountInfo recording, answered with no account
+    let account = dir.join("rpc-ee5c08451f0db464.json");
+    let mut recording: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&account).unwrap()).unwrap();
+    recording["responses"][0]["result"]["value"] = serde_json::Value::Null;
+    std::fs::write(&account, recording.to_string()).unwrap();
+
+    let output = replay_dir(&dir, &["get-bids", AUCTION_STATE, "--strict"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(output.contains("Reconciliation: OK"), "{}", output);
+    assert!(
+        output.contains("Result From: highest bid (account closed)"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("Winner: B88xH3Jmhq4WEaiRno2mYmsxV35MmgSY45ZmQnbL8yft"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn efficiency_against_quote() {
+    assert_eq!(
+        replay(
+            "auction-efficiency",
+            &["efficiency", ORDER_ID, "--quote-api", "http://quotes.test"]
+        ),
+        expected("auction-efficiency", "efficiency.out")
+    );
+}
+
+#[test]
+fn missing_recording_fails() {
+    let output = Command::new(env!("CARGO_BIN_EXE_mayan-cli"))
+        .arg("--replay")
+        .arg(fixture_dir("swift-auction"))
+        .args([
+            "get-auction-state",
+            "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin",
+        ])
+        .output()
+        .expect("failed to run mayan-cli");
+    assert!(!output.status.success());
+}
@@ -0,0 +1,59 @@
+//! Runs a command against local mock RPC endpoints to check that
+//! `--rpc-timeout` gives up on an endpoint that does not answer and fails over
+//! to the next one, and that `--min-context-slot` is sent along.
+
+mod common;
+
+use common::{fixture_dir, recorded_auction_state, rpc_endpoint, slow_endpoint, AUCTION_STATE};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const MIN_CONTEXT_SLOT: u64 = 340_000_000;
+
+#[test]
+fn slow_endpoint_times_out_and_fails_over() {
+    let slow = slow_endpoint();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let good = rpc_endpoint(requests.clone(), recorded_auction_state());
+
+    let start = Instant::now();
+    let output = Command::new(env!("CARGO_BIN_EXE_mayan-cli"))
+        .args(["--rpc-timeout", "1"])
+        .args(["--min-context-slot", &MIN_CONTEXT_SLOT.to_string()])
+        .args(["get-auction-state", AUCTION_STATE])
+        .args(["--rpc-url", &format!("{},{}", slow, good)])
+        .env("NO_COLOR", "1")
+        .env_remove("SOLANA_RPC_URL")
+        .output()
+        .expect("failed to run mayan-cli");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "mayan-cli failed: {}", stderr);
+    assert!(
+        start.elapsed() < Duration::from_secs(10),
+        "took {:?}, the slow endpoint was not timed out",
+        start.elapsed()
+    );
+    assert!(
+        stderr.contains(&format!("Warning: RPC endpoint {} failed", slow)),
+        "no failover warning: {}",
+        stderr
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        std::fs::read_to_string(fixture_dir("swift-auction").join("get-auction-state.out"))
+            .unwrap()
+    );
+
+    let requests = requests.lock().unwrap();
+    assert!(!requests.is_empty());
+    for request in requests.iter() {
+        let request = request.json();
+        assert_eq!(
+            request["params"][1]["minContextSlot"], MIN_CONTEXT_SLOT,
+            "{}",
+            request
+        );
+    }
+}